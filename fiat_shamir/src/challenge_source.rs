@@ -0,0 +1,163 @@
+use ark_ff::PrimeField;
+use std::io::Write;
+
+use crate::fiat_shamir_transcript::{field_vec_to_bytes, Transcript};
+
+/// Where a protocol draws its verifier challenges from: a Fiat-Shamir
+/// transcript, or a fixed sequence for driving the protocol interactively.
+pub trait ChallengeSource<F: PrimeField> {
+    fn append(&mut self, preimage: &[u8]);
+
+    fn get_random_challenge(&mut self) -> F;
+
+    fn get_random_challenges(&mut self, n: usize) -> Vec<F> {
+        (0..n).map(|_| self.get_random_challenge()).collect()
+    }
+
+    /// Draws again until the challenge is nonzero, for challenges that scale
+    /// a claim, where zero would drop the claim altogether. A transcript
+    /// squeezes zero with negligible probability, so this almost always
+    /// matches `get_random_challenge`.
+    fn get_nonzero_challenge(&mut self) -> F {
+        loop {
+            let challenge = self.get_random_challenge();
+
+            if !challenge.is_zero() {
+                return challenge;
+            }
+        }
+    }
+
+    fn append_field(&mut self, x: F) {
+        self.append_fields(&[x])
+    }
+
+    fn append_fields(&mut self, xs: &[F]) {
+        self.append(&field_vec_to_bytes(xs))
+    }
+
+    /// Absorbs a marker for entering `phase` at `index`, so transcripts of
+    /// different protocol phases differ even where their field data
+    /// coincides. The tag is length-prefixed, so no two markers share an
+    /// encoding.
+    fn absorb_phase(&mut self, phase: &str, index: usize) {
+        let mut marker = Vec::with_capacity(phase.len() + 16);
+        marker.extend((phase.len() as u64).to_le_bytes());
+        marker.extend(phase.as_bytes());
+        marker.extend((index as u64).to_le_bytes());
+
+        self.append(&marker)
+    }
+}
+
+impl<F: PrimeField, W: Write> ChallengeSource<F> for Transcript<F, W> {
+    fn append(&mut self, preimage: &[u8]) {
+        Transcript::append(self, preimage)
+    }
+
+    fn get_random_challenge(&mut self) -> F {
+        Transcript::get_random_challenge(self)
+    }
+}
+
+/// Hands out the given challenges in order and ignores absorbed data.
+#[derive(Debug, Clone)]
+pub struct FixedChallenges<F: PrimeField> {
+    challenges: Vec<F>,
+    next: usize,
+}
+
+impl<F: PrimeField> FixedChallenges<F> {
+    pub fn new(challenges: Vec<F>) -> Self {
+        Self {
+            challenges,
+            next: 0,
+        }
+    }
+}
+
+impl<F: PrimeField> ChallengeSource<F> for FixedChallenges<F> {
+    fn append(&mut self, _preimage: &[u8]) {}
+
+    fn get_random_challenge(&mut self) -> F {
+        let challenge = *self
+            .challenges
+            .get(self.next)
+            .expect("Fixed challenge source exhausted");
+        self.next += 1;
+
+        challenge
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn it_returns_fixed_challenges_in_order() {
+        let mut source =
+            FixedChallenges::new(vec![Fq::from(3), Fq::from(5), Fq::from(7), Fq::from(11)]);
+
+        source.append_field(Fq::from(9));
+
+        assert_eq!(source.get_random_challenge(), Fq::from(3));
+        assert_eq!(source.get_random_challenge(), Fq::from(5));
+        assert_eq!(
+            source.get_random_challenges(2),
+            vec![Fq::from(7), Fq::from(11)]
+        );
+    }
+
+    #[test]
+    fn it_squeezes_again_past_zero_challenges() {
+        let mut source = FixedChallenges::new(vec![
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(5),
+            Fq::from(0),
+            Fq::from(7),
+        ]);
+
+        assert_eq!(source.get_nonzero_challenge(), Fq::from(5));
+        assert_eq!(source.get_nonzero_challenge(), Fq::from(7));
+
+        let mut transcript_1: Transcript<Fq> = Transcript::new();
+        let mut transcript_2: Transcript<Fq> = Transcript::new();
+
+        assert_eq!(
+            transcript_1.get_nonzero_challenge(),
+            transcript_2.get_random_challenge()
+        );
+    }
+
+    #[test]
+    fn it_matches_the_transcript_through_the_trait() {
+        let mut transcript_1: Transcript<Fq> = Transcript::new();
+        let mut transcript_2: Transcript<Fq> = Transcript::new();
+
+        ChallengeSource::append_fields(&mut transcript_1, &[Fq::from(1), Fq::from(2)]);
+        transcript_2.append_fields(&[Fq::from(1), Fq::from(2)]);
+
+        assert_eq!(
+            ChallengeSource::get_random_challenge(&mut transcript_1),
+            transcript_2.get_random_challenge()
+        );
+    }
+
+    #[test]
+    fn it_separates_phases_over_the_same_data() {
+        let challenge = |phase: &str, index: usize| {
+            let mut transcript: Transcript<Fq> = Transcript::new();
+            transcript.absorb_phase(phase, index);
+            transcript.append_field(Fq::from(1));
+
+            ChallengeSource::get_random_challenge(&mut transcript)
+        };
+
+        assert_eq!(challenge("layer_start", 1), challenge("layer_start", 1));
+        assert_ne!(challenge("layer_start", 1), challenge("layer_start", 2));
+        assert_ne!(challenge("layer_start", 1), challenge("layer_end", 1));
+    }
+}
@@ -1,34 +1,124 @@
 use ark_ff::{BigInteger, PrimeField};
 use sha3::{Digest, Keccak256};
+use std::io::{self, Write};
 use std::marker::PhantomData;
 
 #[derive(Clone)]
-pub struct Transcript<F: PrimeField> {
+pub struct Transcript<F: PrimeField, W = io::Sink> {
     _field: PhantomData<F>,
     hasher: Keccak256,
+    writer: W,
+    writer_error: Option<io::ErrorKind>,
 }
 
 impl<F: PrimeField> Transcript<F> {
     pub fn new() -> Self {
+        Self::with_writer(io::sink())
+    }
+}
+
+impl<F: PrimeField, W: Write> Transcript<F, W> {
+    /// A transcript that also forwards every appended byte to `writer`, e.g.
+    /// an external hasher. Challenges still come from the transcript's own
+    /// state, and the squeezed challenge bytes are not forwarded. The first
+    /// error `writer` returns is kept for `writer_error` and `finish`, and
+    /// nothing more is forwarded after it.
+    pub fn with_writer(writer: W) -> Self {
         Self {
             _field: PhantomData,
             hasher: Keccak256::new(),
+            writer,
+            writer_error: None,
+        }
+    }
+
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    /// The kind of the first error the writer returned, if any.
+    pub fn writer_error(&self) -> Option<io::ErrorKind> {
+        self.writer_error
+    }
+
+    /// Flushes the writer and returns it, or the first error it returned.
+    pub fn finish(mut self) -> io::Result<W> {
+        match self.writer_error {
+            Some(kind) => Err(kind.into()),
+            None => self.writer.flush().map(|_| self.writer),
         }
     }
 
     pub fn append(&mut self, preimage: &[u8]) {
+        if self.writer_error.is_none() {
+            if let Err(err) = self.writer.write_all(preimage) {
+                self.writer_error = Some(err.kind());
+            }
+        }
+
         self.hasher.update(preimage)
     }
 
+    pub fn append_field(&mut self, x: F) {
+        self.append_fields(&[x])
+    }
+
+    pub fn append_fields(&mut self, xs: &[F]) {
+        self.append(&field_vec_to_bytes(xs))
+    }
+
     pub fn get_random_challenge(&mut self) -> F {
         let random_challenge = self.hasher.finalize_reset();
 
-        self.append(&random_challenge);
+        self.hasher.update(random_challenge);
 
         F::from_le_bytes_mod_order(&random_challenge)
     }
+
+    /// Like `get_random_challenge`, but reads the Keccak digest as a
+    /// big-endian integer before reducing it mod the field order, for
+    /// verifiers specified that way. The transcript state advances exactly as
+    /// it does for `get_random_challenge`.
+    pub fn get_challenge_be_reduced(&mut self) -> F {
+        let random_challenge = self.hasher.finalize_reset();
+
+        self.hasher.update(random_challenge);
+
+        F::from_be_bytes_mod_order(&random_challenge)
+    }
+
+    /// `n` challenges, each squeezed and absorbed back in turn exactly as
+    /// `n` calls to `get_random_challenge` would.
+    pub fn get_random_challenges(&mut self, n: usize) -> Vec<F> {
+        (0..n).map(|_| self.get_random_challenge()).collect()
+    }
+
+    pub fn state_digest(&self) -> [u8; 32] {
+        self.hasher.clone().finalize().into()
+    }
 }
 
+impl<F: PrimeField> Default for Transcript<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Canonical compressed encoding of each element, back to back. Every element
+/// takes `F::zero().compressed_size()` little-endian bytes, so fields of the
+/// same size share a layout.
+pub fn field_vec_to_bytes<F: PrimeField>(xs: &[F]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(xs.len() * F::zero().compressed_size());
+
+    for x in xs {
+        x.serialize_compressed(&mut bytes)
+            .expect("writing to a Vec cannot fail");
+    }
+
+    bytes
+}
+
+#[deprecated(note = "use `field_vec_to_bytes`")]
 pub fn fq_vec_to_bytes<F: PrimeField>(values: &[F]) -> Vec<u8> {
     values
         .iter()
@@ -38,8 +128,13 @@ pub fn fq_vec_to_bytes<F: PrimeField>(values: &[F]) -> Vec<u8> {
 
 #[cfg(test)]
 mod test {
-    use super::Transcript;
+    #![allow(deprecated)]
+
+    use super::{field_vec_to_bytes, fq_vec_to_bytes, Transcript};
+    use ark_bls12_381::Fr;
     use ark_bn254::Fq;
+    use std::io;
+    use std::str::FromStr;
 
     #[test]
     fn it_hashes() {
@@ -50,4 +145,150 @@ mod test {
 
         dbg!(random_challenge);
     }
+
+    #[test]
+    fn it_appends_field_elements_like_bytes() {
+        let mut transcript_1: Transcript<Fq> = Transcript::new();
+        let mut transcript_2: Transcript<Fq> = Transcript::new();
+
+        transcript_1.append_field(Fq::from(7));
+        transcript_2.append(&fq_vec_to_bytes(&[Fq::from(7)]));
+
+        assert_eq!(
+            transcript_1.get_random_challenge(),
+            transcript_2.get_random_challenge()
+        );
+
+        let values = [Fq::from(1), Fq::from(2), Fq::from(3)];
+        transcript_1.append_fields(&values);
+        transcript_2.append(&fq_vec_to_bytes(&values));
+
+        assert_eq!(
+            transcript_1.get_random_challenge(),
+            transcript_2.get_random_challenge()
+        );
+    }
+
+    #[test]
+    fn it_encodes_any_prime_field_canonically() {
+        let fq_values = [Fq::from(7), -Fq::from(1)];
+        let fr_values = [Fr::from(7), -Fr::from(1)];
+
+        let fq_bytes = field_vec_to_bytes(&fq_values);
+        let fr_bytes = field_vec_to_bytes(&fr_values);
+
+        assert_eq!(fq_bytes, field_vec_to_bytes(&fq_values));
+        assert_eq!(fr_bytes, field_vec_to_bytes(&fr_values));
+        assert_eq!(fq_bytes.len(), 64);
+        assert_eq!(fr_bytes.len(), 64);
+
+        assert_eq!(fq_bytes[..32], fr_bytes[..32]);
+        assert_ne!(fq_bytes[32..], fr_bytes[32..]);
+        assert_ne!(fq_bytes[..32], fq_bytes[32..]);
+
+        assert_eq!(fq_bytes, fq_vec_to_bytes(&fq_values));
+        assert_eq!(fr_bytes, fq_vec_to_bytes(&fr_values));
+    }
+
+    #[test]
+    fn it_reduces_big_endian_challenges_per_spec() {
+        let mut transcript_1: Transcript<Fq> = Transcript::new();
+        let mut transcript_2: Transcript<Fq> = Transcript::new();
+
+        transcript_1.append("zero knowledge".as_bytes());
+        transcript_2.append("zero knowledge".as_bytes());
+
+        // keccak256("zero knowledge") read big-endian, mod the bn254 base field.
+        let expected = Fq::from_str(
+            "1919968899701721269527863758916621665983533614239389167860814330304581837877",
+        )
+        .unwrap();
+
+        let be_challenge = transcript_1.get_challenge_be_reduced();
+        assert_eq!(be_challenge, expected);
+        assert_ne!(be_challenge, transcript_2.get_random_challenge());
+
+        assert_eq!(transcript_1.state_digest(), transcript_2.state_digest());
+    }
+
+    #[test]
+    fn it_squeezes_several_challenges_like_sequential_calls() {
+        let mut transcript_1: Transcript<Fq> = Transcript::new();
+        let mut transcript_2: Transcript<Fq> = Transcript::new();
+
+        transcript_1.append("zero knowledge".as_bytes());
+        transcript_2.append("zero knowledge".as_bytes());
+
+        let sequential: Vec<Fq> = (0..3)
+            .map(|_| transcript_1.get_random_challenge())
+            .collect();
+
+        assert_eq!(transcript_2.get_random_challenges(3), sequential);
+        assert_eq!(transcript_1.state_digest(), transcript_2.state_digest());
+        assert!(transcript_2.get_random_challenges(0).is_empty());
+    }
+
+    #[test]
+    fn it_returns_state_digest_without_changing_state() {
+        let mut transcript_1: Transcript<Fq> = Transcript::new();
+        let mut transcript_2: Transcript<Fq> = Transcript::new();
+
+        transcript_1.append("zero knowledge".as_bytes());
+        transcript_2.append("zero knowledge".as_bytes());
+
+        assert_eq!(transcript_1.state_digest(), transcript_1.state_digest());
+        assert_eq!(transcript_1.state_digest(), transcript_2.state_digest());
+
+        transcript_2.append("proof".as_bytes());
+
+        assert_ne!(transcript_1.state_digest(), transcript_2.state_digest());
+        assert_ne!(
+            transcript_1.get_random_challenge(),
+            transcript_2.get_random_challenge()
+        );
+    }
+
+    #[test]
+    fn it_forwards_appended_bytes_to_the_writer() {
+        let mut plain: Transcript<Fq> = Transcript::new();
+        let mut writing: Transcript<Fq, Vec<u8>> = Transcript::with_writer(Vec::new());
+
+        let appends: [&[u8]; 3] = [b"zero knowledge", b"", b"proof"];
+        let mut expected = Vec::new();
+
+        for bytes in appends {
+            plain.append(bytes);
+            writing.append(bytes);
+            expected.extend_from_slice(bytes);
+
+            assert_eq!(plain.get_random_challenge(), writing.get_random_challenge());
+        }
+
+        writing.append_field(Fq::from(7));
+        expected.extend(field_vec_to_bytes(&[Fq::from(7)]));
+
+        assert_eq!(writing.writer_error(), None);
+        assert_eq!(writing.finish().unwrap(), expected);
+    }
+
+    #[test]
+    fn it_keeps_the_first_writer_error() {
+        let mut plain: Transcript<Fq> = Transcript::new();
+        let mut buffer = [0; 4];
+        let mut writing: Transcript<Fq, &mut [u8]> = Transcript::with_writer(&mut buffer);
+
+        for bytes in [b"zero".as_slice(), b"knowledge", b"proof"] {
+            plain.append(bytes);
+            writing.append(bytes);
+
+            assert_eq!(plain.get_random_challenge(), writing.get_random_challenge());
+        }
+
+        assert_eq!(writing.writer_error(), Some(io::ErrorKind::WriteZero));
+        assert_eq!(
+            writing.finish().unwrap_err().kind(),
+            io::ErrorKind::WriteZero
+        );
+        assert_eq!(&buffer, b"zero");
+    }
 }
@@ -1 +1,2 @@
+pub mod challenge_source;
 pub mod fiat_shamir_transcript;
@@ -0,0 +1,144 @@
+use ark_bn254::Fq;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use gkr::{
+    gkr_circuit::{Circuit, Operation},
+    gkr_protocol::{get_folded_selectors, prove, prove_low_memory, verify, verify_parallel},
+};
+
+/// Builds a balanced circuit over 32 inputs: 16 mul gates, then alternating
+/// add/mul layers down to a single output gate.
+fn deep_circuit() -> (Circuit<Fq>, Vec<Fq>) {
+    let structure: Vec<Vec<Operation>> = vec![
+        vec![Operation::Mul; 16],
+        vec![Operation::Add; 8],
+        vec![Operation::Mul; 4],
+        vec![Operation::Add; 2],
+        vec![Operation::Add],
+    ];
+
+    let inputs: Vec<Fq> = (0..32).map(|i| Fq::from(i as u64)).collect();
+
+    (Circuit::new(structure), inputs)
+}
+
+/// Benchmarks the standard prover, which keeps every layer's evaluations in
+/// memory for the whole run.
+pub fn prove_benchmark(c: &mut Criterion) {
+    let (circuit, inputs) = deep_circuit();
+
+    c.bench_function("GKR Prove", |b| {
+        b.iter(|| {
+            let mut circuit = circuit.clone();
+            let proof = prove(black_box(&mut circuit), black_box(&inputs));
+            black_box(proof);
+        })
+    });
+}
+
+/// Benchmarks the low-memory prover. It only holds one layer's values at a
+/// time, re-evaluating the circuit from the inputs for every layer instead.
+/// Peak witness memory drops from the sum of all layer widths to the widest
+/// layer, while gate evaluations grow quadratically with depth. Sum-check
+/// dominates proving time, so the slowdown should be small for shallow circuits.
+pub fn prove_low_memory_benchmark(c: &mut Criterion) {
+    let (circuit, inputs) = deep_circuit();
+
+    c.bench_function("GKR Prove low memory", |b| {
+        b.iter(|| {
+            let proof = prove_low_memory(black_box(&circuit), black_box(&inputs));
+            black_box(proof);
+        })
+    });
+}
+
+/// Compares folding a layer's selectors with one shared weight table against
+/// four separate `multi_partial_evaluate` calls on the 16-gate layer.
+pub fn folded_selectors_benchmark(c: &mut Criterion) {
+    let (circuit, _) = deep_circuit();
+    let layer = &circuit.layers[0];
+    let r_b: Vec<Fq> = (0..4).map(|i| Fq::from(i as u64 + 2)).collect();
+    let r_c: Vec<Fq> = (0..4).map(|i| Fq::from(i as u64 + 11)).collect();
+    let alpha = Fq::from(3);
+    let beta = Fq::from(7);
+
+    c.bench_function("GKR folded selectors", |b| {
+        b.iter(|| {
+            let selectors = get_folded_selectors(
+                black_box(layer),
+                black_box(&r_b),
+                black_box(&r_c),
+                alpha,
+                beta,
+            );
+            black_box(selectors);
+        })
+    });
+
+    c.bench_function("GKR partially evaluated selectors", |b| {
+        b.iter(|| {
+            let add_i = layer.get_add_mul_i(Operation::Add);
+            let mul_i = layer.get_add_mul_i(Operation::Mul);
+
+            let summed_add_i = add_i.multi_partial_evaluate(black_box(&r_b)).scale(alpha)
+                + add_i.multi_partial_evaluate(black_box(&r_c)).scale(beta);
+            let summed_mul_i = mul_i.multi_partial_evaluate(black_box(&r_b)).scale(alpha)
+                + mul_i.multi_partial_evaluate(black_box(&r_c)).scale(beta);
+
+            black_box((summed_add_i, summed_mul_i));
+        })
+    });
+}
+
+/// Compares sequential verification with `verify_parallel` on a 6-layer
+/// circuit over 64 inputs. The parallel verifier still derives every
+/// challenge in one sequential pass, so only the per-layer checks overlap.
+pub fn verify_parallel_benchmark(c: &mut Criterion) {
+    let structure: Vec<Vec<Operation>> = (0..6)
+        .map(|layer| {
+            let op = if layer % 2 == 0 {
+                Operation::Mul
+            } else {
+                Operation::Add
+            };
+
+            vec![op; 32 >> layer]
+        })
+        .collect();
+    let inputs: Vec<Fq> = (0..64).map(|i| Fq::from(i as u64)).collect();
+
+    let mut circuit = Circuit::new(structure);
+    let proof = prove(&mut circuit, &inputs);
+
+    c.bench_function("GKR Verify", |b| {
+        b.iter_batched(
+            || prove(&mut circuit.clone(), &inputs),
+            |proof| {
+                black_box(verify(
+                    proof,
+                    black_box(circuit.clone()),
+                    black_box(&inputs),
+                ))
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    c.bench_function("GKR Verify parallel", |b| {
+        b.iter(|| {
+            black_box(verify_parallel(
+                black_box(&proof),
+                black_box(&circuit),
+                black_box(&inputs),
+            ))
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    prove_benchmark,
+    prove_low_memory_benchmark,
+    folded_selectors_benchmark,
+    verify_parallel_benchmark
+);
+criterion_main!(benches);
@@ -0,0 +1,151 @@
+use ark_ff::PrimeField;
+use fiat_shamir::challenge_source::ChallengeSource;
+use multilinear_polynomial::multilinear_polynomial_evaluation::MultilinearPoly;
+use std::marker::PhantomData;
+
+/// The order in which the GKR prover and verifier absorb proof data and draw
+/// challenges. Both sides go through these methods, so they cannot drift
+/// apart: the output poly and `m_0` around the output challenge, then for
+/// every layer a `layer_start` phase marker and the round polys of its
+/// sum-check, and `o_1, alpha, o_2, beta` and, for a layer sending one,
+/// `o_3, gamma` between layers.
+///
+/// It is a `ChallengeSource` itself, so the sum-check of each layer draws
+/// from it too.
+pub struct ChallengeStream<'a, F: PrimeField, C: ChallengeSource<F>> {
+    source: &'a mut C,
+    _field: PhantomData<F>,
+}
+
+impl<'a, F: PrimeField, C: ChallengeSource<F>> ChallengeStream<'a, F, C> {
+    pub fn new(source: &'a mut C) -> Self {
+        Self {
+            source,
+            _field: PhantomData,
+        }
+    }
+
+    /// Absorbs the output poly and draws the output challenge `r`, returning
+    /// `(m_0, r)` with `m_0` the output poly at `r`.
+    pub fn output_claim(&mut self, output_poly: &MultilinearPoly<F>) -> (F, F) {
+        self.source.append_fields(&output_poly.evaluation);
+
+        // m_0 is never sent in the proof: both sides derive it from the output
+        // poly, which is absorbed before the challenge is drawn. Changing the
+        // output poly changes the challenge, m_0 and every later challenge, so
+        // it cannot be swapped without breaking the first sum-check.
+        let random_challenge = self.source.get_random_challenge();
+        let m_0 = output_poly.evaluate(vec![random_challenge]);
+
+        self.source.append_field(m_0);
+
+        (m_0, random_challenge)
+    }
+
+    /// Marks the start of the layer at top-down index `index`, before any of
+    /// its round polys are absorbed.
+    pub fn start_layer(&mut self, index: usize) {
+        self.source.absorb_phase("layer_start", index);
+    }
+
+    /// Absorbs a sum-check round poly and draws that round's challenge, as
+    /// `gkr_prove` and `gkr_verify` do.
+    pub fn next_layer_challenge(&mut self, round_poly: &[F]) -> F {
+        self.source.append_fields(round_poly);
+        self.source.get_random_challenge()
+    }
+
+    /// Absorbs the claims `o_1, o_2` about the layer below and draws the
+    /// nonzero `(alpha, beta)` reducing them to one.
+    pub fn reduce(&mut self, o_1: F, o_2: F) -> (F, F) {
+        self.source.append_field(o_1);
+        let alpha = self.source.get_nonzero_challenge();
+
+        self.source.append_field(o_2);
+        let beta = self.source.get_nonzero_challenge();
+
+        (alpha, beta)
+    }
+
+    /// Absorbs a skip connection's claim `o_3` and draws its nonzero weight.
+    pub fn skip_weight(&mut self, o_3: F) -> F {
+        self.source.append_field(o_3);
+        self.source.get_nonzero_challenge()
+    }
+}
+
+impl<F: PrimeField, C: ChallengeSource<F>> ChallengeSource<F> for ChallengeStream<'_, F, C> {
+    fn append(&mut self, preimage: &[u8]) {
+        self.source.append(preimage)
+    }
+
+    fn get_random_challenge(&mut self) -> F {
+        self.source.get_random_challenge()
+    }
+
+    fn absorb_phase(&mut self, phase: &str, index: usize) {
+        self.source.absorb_phase(phase, index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ChallengeStream;
+    use crate::{
+        gkr_protocol::{prove, verify_with_challenges, VerifiedChallenges},
+        test_util::sample_circuit_and_inputs,
+    };
+    use ark_bn254::Fq;
+    use fiat_shamir::{challenge_source::FixedChallenges, fiat_shamir_transcript::Transcript};
+
+    #[test]
+    fn it_replays_the_sequence_the_prover_and_verifier_draw() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let proof = prove(&mut circuit, &inputs);
+
+        let mut transcript = Transcript::new();
+        let mut stream = ChallengeStream::new(&mut transcript);
+        let (_, output_challenge) = stream.output_claim(proof.output_poly());
+
+        let mut layer_challenges = Vec::new();
+        let mut alphas_betas = Vec::new();
+        for (i, round_polys) in proof.proof_polynomials.iter().enumerate() {
+            stream.start_layer(i);
+            layer_challenges.push(
+                round_polys
+                    .iter()
+                    .map(|round_poly| stream.next_layer_challenge(round_poly))
+                    .collect(),
+            );
+
+            if let Some((o_1, o_2)) = proof.claimed_evaluations.get(i) {
+                alphas_betas.push(stream.reduce(*o_1, *o_2));
+            }
+        }
+
+        let replayed = VerifiedChallenges {
+            output_challenge,
+            layer_challenges,
+            alphas_betas,
+            skip_weights: Vec::new(),
+        };
+
+        assert_eq!(
+            verify_with_challenges(proof, circuit, &inputs),
+            Ok(replayed)
+        );
+    }
+
+    #[test]
+    fn it_reduces_claims_with_consecutive_nonzero_challenges() {
+        let mut source =
+            FixedChallenges::new(vec![Fq::from(3), Fq::from(0), Fq::from(5), Fq::from(7)]);
+        let mut stream = ChallengeStream::new(&mut source);
+
+        assert_eq!(
+            stream.reduce(Fq::from(1), Fq::from(2)),
+            (Fq::from(3), Fq::from(5))
+        );
+        assert_eq!(stream.skip_weight(Fq::from(4)), Fq::from(7));
+    }
+}
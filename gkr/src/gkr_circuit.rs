@@ -1,21 +1,106 @@
 use ark_ff::PrimeField;
-use multilinear_polynomial::multilinear_polynomial_evaluation::MultilinearPoly;
+use ark_std::rand::Rng;
+use multilinear_polynomial::{
+    composed_polynomial::{ProductPoly, SumPoly},
+    multilinear_polynomial_evaluation::MultilinearPoly,
+};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::marker::PhantomData;
+use std::ops::Index;
+use std::sync::Arc;
+
+/// Round degree of the fbc sum-check polynomials: every term is a selector
+/// times a w-product, so two multilinear factors.
+pub const FBC_ROUND_DEGREE: usize = 2;
+
+/// Where `min_field_bits` stops searching: `2^1024` overflows an `f64`, so
+/// every soundness error is zero there.
+const MAX_FIELD_BITS: usize = 1024;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Operation {
     Add,
     Mul,
+    /// `a + b - 2ab`, which is XOR only when both inputs are 0 or 1. Nothing
+    /// checks that; other inputs give a proof of the polynomial instead.
+    Xor,
+    /// Reads the circuit table with this id at the index given by its inputs,
+    /// see `lookup_coefficients`.
+    #[cfg(feature = "lookup")]
+    Lookup(usize),
+    /// Applies the circuit gate function with this id, see `GateFn`.
+    Custom(usize),
 }
 
 impl Operation {
+    /// The gate's output on inputs `a` and `b`. Lookup and custom gates need
+    /// their circuit's tables and gate functions, so this panics for them;
+    /// use `try_apply` or `Layer` evaluation instead.
     pub fn apply<F: PrimeField>(self, a: F, b: F) -> F {
+        self.try_apply(a, b)
+            .expect("lookup and custom gates are evaluated by their layer")
+    }
+
+    /// Like `apply`, but `None` for lookup and custom gates.
+    pub fn try_apply<F: PrimeField>(self, a: F, b: F) -> Option<F> {
+        match self {
+            Operation::Add => Some(a + b),
+            Operation::Mul => Some(a * b),
+            Operation::Xor => Some(a + b - (a * b).double()),
+            #[cfg(feature = "lookup")]
+            Operation::Lookup(_) => None,
+            Operation::Custom(_) => None,
+        }
+    }
+
+    fn symbol(self) -> String {
         match self {
-            Operation::Add => a + b,
-            Operation::Mul => a * b,
+            Operation::Add => "+".to_string(),
+            Operation::Mul => "*".to_string(),
+            Operation::Xor => "^".to_string(),
+            #[cfg(feature = "lookup")]
+            Operation::Lookup(id) => format!("T{id}"),
+            Operation::Custom(id) => format!("G{id}"),
         }
     }
 }
 
+/// `(constant, left, right, product)` such that
+/// `constant + left * a + right * b + product * a * b` is `table` at the
+/// index given by bits `a`, `b`: a 2-entry table is indexed by `a` alone and
+/// a 4-entry table by `2a + b`. Like `Xor`, nothing checks that the inputs
+/// are bits.
+#[cfg(feature = "lookup")]
+pub fn lookup_coefficients<F: PrimeField>(table: &[F]) -> [F; 4] {
+    match *table {
+        [t_0, t_1] => [t_0, t_1 - t_0, F::zero(), F::zero()],
+        [t_00, t_01, t_10, t_11] => [t_00, t_10 - t_00, t_01 - t_00, t_00 - t_01 - t_10 + t_11],
+        _ => panic!("lookup tables must have 2 or 4 entries"),
+    }
+}
+
+/// A binary gate given as a polynomial in its inputs, registered with
+/// `Circuit::register_gate` and used through `Operation::Custom`.
+///
+/// `degree` is the highest power of either input, so `a * a + b` has degree
+/// 2 and `a * b` degree 1. The b and c variables of a layer's sum-check are
+/// disjoint, so a layer's round polys have degree one more than its highest
+/// gate degree, and never less than `FBC_ROUND_DEGREE`. Each extra degree
+/// adds an evaluation to every round poly of the layer and `1 / |F|` per
+/// round to the soundness error, see `Circuit::soundness_error`.
+pub trait GateFn<F: PrimeField>: fmt::Debug + Send + Sync {
+    fn apply(&self, a: F, b: F) -> F;
+
+    fn degree(&self) -> usize;
+
+    /// The gate as `(coefficient, left power, right power)` terms, so
+    /// `apply(a, b)` is the sum of `coefficient * a^left * b^right`. Each
+    /// distinct pair of powers in a layer gets its own selector. Nothing
+    /// checks that the terms agree with `apply`.
+    fn selector_contribution(&self) -> Vec<(F, usize, usize)>;
+}
+
 #[derive(Debug, Clone)]
 pub struct Gate<F: PrimeField> {
     pub l_input: F,
@@ -25,13 +110,13 @@ pub struct Gate<F: PrimeField> {
 }
 
 impl<F: PrimeField> Gate<F> {
+    /// A lookup or custom gate's output stays zero until its layer is
+    /// evaluated, since the gate alone has no table or gate function to read.
     pub fn new(l_input: F, r_input: F, op: Operation) -> Self {
-        let output = op.apply(l_input, r_input);
-
         Self {
             l_input,
             r_input,
-            output,
+            output: op.try_apply(l_input, r_input).unwrap_or_default(),
             op,
         }
     }
@@ -40,88 +125,519 @@ impl<F: PrimeField> Gate<F> {
 #[derive(Debug, Clone)]
 pub struct Layer<F: PrimeField> {
     pub gates: Vec<Gate<F>>,
+    /// Optional per-gate public multipliers applied to each gate's output.
+    pub constants: Option<Vec<F>>,
+    /// The circuit's lookup tables, shared in by `Circuit::layer`.
+    #[cfg(feature = "lookup")]
+    pub tables: Arc<Vec<Vec<F>>>,
+    /// The circuit's custom gate functions, shared in by `Circuit::layer`.
+    pub gate_fns: Arc<Vec<Arc<dyn GateFn<F>>>>,
+    /// Gates whose right input is wire `2 * gate + 1` of the layer two below
+    /// instead of the layer directly below, see `Circuit::add_skip_connection`.
+    pub skip_connections: Vec<usize>,
 }
 
 impl<F: PrimeField> Layer<F> {
     pub fn new(gates: Vec<Gate<F>>) -> Self {
-        Self { gates }
+        Self {
+            gates,
+            constants: None,
+            #[cfg(feature = "lookup")]
+            tables: Arc::default(),
+            gate_fns: Arc::default(),
+            skip_connections: Vec::new(),
+        }
+    }
+
+    pub fn with_constants(mut gates: Vec<Gate<F>>, constants: Vec<F>) -> Self {
+        if gates.len() != constants.len() {
+            panic!("there must be one constant per gate");
+        }
+
+        for (gate, constant) in gates.iter_mut().zip(&constants) {
+            gate.output *= constant;
+        }
+
+        Self {
+            gates,
+            constants: Some(constants),
+            #[cfg(feature = "lookup")]
+            tables: Arc::default(),
+            gate_fns: Arc::default(),
+            skip_connections: Vec::new(),
+        }
+    }
+
+    pub fn gate_constant(&self, gate_index: usize) -> F {
+        match &self.constants {
+            Some(constants) => constants[gate_index],
+            None => F::one(),
+        }
+    }
+
+    /// Whether the output of gate `gate_index` depends on its left and its
+    /// right input. A gate scaled by a zero constant reads neither, a lookup
+    /// gate only the inputs its table's coefficients depend on, and a custom
+    /// gate only those with a nonzero power in one of its terms.
+    pub fn reads_inputs(&self, gate_index: usize) -> (bool, bool) {
+        if self.gate_constant(gate_index).is_zero() {
+            return (false, false);
+        }
+
+        match self.gates[gate_index].op {
+            #[cfg(feature = "lookup")]
+            Operation::Lookup(id) => {
+                let [_, left, right, product] = lookup_coefficients(&self.tables[id]);
+
+                (
+                    !left.is_zero() || !product.is_zero(),
+                    !right.is_zero() || !product.is_zero(),
+                )
+            }
+            Operation::Custom(id) => self.gate_fns[id]
+                .selector_contribution()
+                .into_iter()
+                .filter(|(coefficient, _, _)| !coefficient.is_zero())
+                .fold((false, false), |(left, right), (_, a_power, b_power)| {
+                    (left || a_power > 0, right || b_power > 0)
+                }),
+            _ => (true, true),
+        }
     }
 
     pub fn get_layer_poly(&self) -> Vec<F> {
         self.gates.iter().map(|gate| gate.output).collect()
     }
 
+    pub fn evaluate(&self, inputs: &[F]) -> Vec<F> {
+        self.evaluate_with_skips(inputs, &[])
+    }
+
+    /// Like `evaluate`, with skip connections reading `inputs_two_below`.
+    pub fn evaluate_with_skips(&self, inputs_below: &[F], inputs_two_below: &[F]) -> Vec<F> {
+        (0..self.gates.len().min(inputs_below.len() / 2))
+            .map(|gate| self.gate_contribution_with_skips(gate, inputs_below, inputs_two_below))
+            .collect()
+    }
+
+    /// The output of gate `gate`, which reads wires `2 * gate` and
+    /// `2 * gate + 1` of `inputs_below`, scaled by its constant.
+    pub fn gate_contribution(&self, gate: usize, inputs_below: &[F]) -> F {
+        self.gate_contribution_with_skips(gate, inputs_below, &[])
+    }
+
+    /// Like `gate_contribution`, but a skip connection reads its right input
+    /// from `inputs_two_below`.
+    pub fn gate_contribution_with_skips(
+        &self,
+        gate: usize,
+        inputs_below: &[F],
+        inputs_two_below: &[F],
+    ) -> F {
+        let l_input = inputs_below[2 * gate];
+        let r_input = match self.is_skip_connection(gate) {
+            true => inputs_two_below[2 * gate + 1],
+            false => inputs_below[2 * gate + 1],
+        };
+
+        self.apply_gate(self.gates[gate].op, l_input, r_input) * self.gate_constant(gate)
+    }
+
+    pub fn has_skip_connections(&self) -> bool {
+        !self.skip_connections.is_empty()
+    }
+
+    pub fn is_skip_connection(&self, gate: usize) -> bool {
+        self.skip_connections.contains(&gate)
+    }
+
+    /// Indices of gate `gate`'s inputs in the wire tables of `wire_tables`.
+    pub fn input_wires(&self, gate: usize) -> (usize, usize) {
+        match self.is_skip_connection(gate) {
+            true => (2 * gate, self.skip_offset() + 2 * gate + 1),
+            false => (2 * gate, 2 * gate + 1),
+        }
+    }
+
+    /// The tables the b and c variables of this layer's fbc poly range over.
+    /// Without skip connections both are `inputs_below`. Otherwise both
+    /// span twice the layer two below: the b table is `inputs_below`
+    /// zero-padded, and the c table is `inputs_below` zero-padded to half its
+    /// length followed by `inputs_two_below`.
+    pub fn wire_tables(&self, inputs_below: &[F], inputs_two_below: &[F]) -> (Vec<F>, Vec<F>) {
+        if !self.has_skip_connections() {
+            return (inputs_below.to_vec(), inputs_below.to_vec());
+        }
+
+        let offset = self.skip_offset();
+
+        let mut w_b = inputs_below.to_vec();
+        w_b.resize(2 * offset, F::zero());
+
+        let mut w_c = inputs_below.to_vec();
+        w_c.resize(offset, F::zero());
+        w_c.extend_from_slice(inputs_two_below);
+        w_c.resize(2 * offset, F::zero());
+
+        (w_b, w_c)
+    }
+
+    /// Where the layer two below starts in the c table of a layer with skip
+    /// connections.
+    fn skip_offset(&self) -> usize {
+        1 << (self.selector_widths().1 - 1)
+    }
+
+    fn apply_gate(&self, op: Operation, a: F, b: F) -> F {
+        match op {
+            #[cfg(feature = "lookup")]
+            Operation::Lookup(id) => {
+                let [constant, left, right, product] = lookup_coefficients(&self.tables[id]);
+
+                constant + left * a + right * b + product * a * b
+            }
+            Operation::Custom(id) => self.gate_fns[id].apply(a, b),
+            _ => op.apply(a, b),
+        }
+    }
+
     pub fn get_add_mul_i(&self, op: Operation) -> MultilinearPoly<F> {
         let n_bits = self.get_bits_for_gates();
         let layer_size = 1 << n_bits;
         let mut poly_eval = vec![F::zero(); layer_size];
 
         let gate_values = self.gate_to_bits();
-        for (gate_value, gate) in gate_values.into_iter().zip(&self.gates) {
+        for (idx, (gate_value, gate)) in gate_values.into_iter().zip(&self.gates).enumerate() {
             if gate.op == op {
-                poly_eval[gate_value] = F::one();
+                poly_eval[gate_value] = self.gate_constant(idx);
             }
         }
 
         MultilinearPoly::new(poly_eval)
     }
 
-    fn get_bits_for_gates(&self) -> u32 {
+    /// The selectors of the fbc poly's `w_b + w_c` and `w_b * w_c` terms. An
+    /// Xor gate's `w_b + w_c - 2 * w_b * w_c` is split across both, adding its
+    /// constant to the first and minus twice its constant to the second.
+    /// Custom gates only have selectors of their own, see
+    /// `get_custom_selectors`.
+    pub fn get_fbc_selectors(&self) -> (MultilinearPoly<F>, MultilinearPoly<F>) {
+        let layer_size = 1 << self.get_bits_for_gates();
+        let mut sum_selector = vec![F::zero(); layer_size];
+        let mut product_selector = vec![F::zero(); layer_size];
+
+        let gate_values = self.gate_to_bits();
+        for (idx, (gate_value, gate)) in gate_values.into_iter().zip(&self.gates).enumerate() {
+            let constant = self.gate_constant(idx);
+
+            match gate.op {
+                Operation::Add => sum_selector[gate_value] += constant,
+                Operation::Mul => product_selector[gate_value] += constant,
+                Operation::Xor => {
+                    sum_selector[gate_value] += constant;
+                    product_selector[gate_value] -= constant.double();
+                }
+                #[cfg(feature = "lookup")]
+                Operation::Lookup(id) => {
+                    product_selector[gate_value] +=
+                        constant * lookup_coefficients(&self.tables[id])[3];
+                }
+                Operation::Custom(_) => {}
+            }
+        }
+
+        (
+            MultilinearPoly::new(sum_selector),
+            MultilinearPoly::new(product_selector),
+        )
+    }
+
+    #[cfg(feature = "lookup")]
+    pub fn has_lookups(&self) -> bool {
+        self.gates
+            .iter()
+            .any(|gate| matches!(gate.op, Operation::Lookup(_)))
+    }
+
+    /// The selectors of the fbc poly's constant, `w_b` and `w_c` terms, which
+    /// only lookup gates contribute to; their product coefficient goes to
+    /// the `w_b * w_c` selector of `get_fbc_selectors`.
+    #[cfg(feature = "lookup")]
+    pub fn get_lookup_selectors(&self) -> [MultilinearPoly<F>; 3] {
+        let layer_size = 1 << self.get_bits_for_gates();
+        let mut selectors: [Vec<F>; 3] = std::array::from_fn(|_| vec![F::zero(); layer_size]);
+
+        let gate_values = self.gate_to_bits();
+        for (idx, (gate_value, gate)) in gate_values.into_iter().zip(&self.gates).enumerate() {
+            if let Operation::Lookup(id) = gate.op {
+                let coefficients = lookup_coefficients(&self.tables[id]);
+
+                for (selector, coefficient) in selectors.iter_mut().zip(coefficients) {
+                    selector[gate_value] += self.gate_constant(idx) * coefficient;
+                }
+            }
+        }
+
+        selectors.map(MultilinearPoly::new)
+    }
+
+    pub fn has_custom_gates(&self) -> bool {
+        self.gates
+            .iter()
+            .any(|gate| matches!(gate.op, Operation::Custom(_)))
+    }
+
+    /// One selector per distinct `(left power, right power)` term of the
+    /// layer's custom gates, in increasing order of powers, holding each
+    /// gate's constant times its coefficient for that term.
+    pub fn get_custom_selectors(&self) -> Vec<(usize, usize, MultilinearPoly<F>)> {
+        let layer_size = 1 << self.get_bits_for_gates();
+        let mut selectors: BTreeMap<(usize, usize), Vec<F>> = BTreeMap::new();
+
+        let gate_values = self.gate_to_bits();
+        for (idx, (gate_value, gate)) in gate_values.into_iter().zip(&self.gates).enumerate() {
+            if let Operation::Custom(id) = gate.op {
+                for (coefficient, left, right) in self.gate_fns[id].selector_contribution() {
+                    let selector = selectors
+                        .entry((left, right))
+                        .or_insert_with(|| vec![F::zero(); layer_size]);
+
+                    selector[gate_value] += self.gate_constant(idx) * coefficient;
+                }
+            }
+        }
+
+        selectors
+            .into_iter()
+            .map(|((left, right), selector)| (left, right, MultilinearPoly::new(selector)))
+            .collect()
+    }
+
+    /// Degree of every one of this layer's sum-check round polys: one more
+    /// than its highest custom gate degree, or `FBC_ROUND_DEGREE` if that is
+    /// larger.
+    pub fn round_degree(&self) -> usize {
+        self.gates
+            .iter()
+            .filter_map(|gate| match gate.op {
+                Operation::Custom(id) => Some(self.gate_fns[id].degree() + 1),
+                _ => None,
+            })
+            .fold(FBC_ROUND_DEGREE, usize::max)
+    }
+
+    /// `(output_index, left_input, right_input)` for every nonzero entry of
+    /// the `op` selector, decoded from its boolean index.
+    pub fn wiring(&self, op: Operation) -> Vec<(usize, usize, usize)> {
+        let (a_bits, bc_bits) = self.selector_widths();
+        let bc_mask = (1 << bc_bits) - 1;
+
+        self.get_add_mul_i(op)
+            .evaluation
+            .iter()
+            .enumerate()
+            .filter(|(_, eval)| !eval.is_zero())
+            .map(|(index, _)| {
+                let c = index & bc_mask;
+                let b = (index >> bc_bits) & bc_mask;
+                let a = (index >> (2 * bc_bits)) & ((1 << a_bits) - 1);
+
+                (a, b, c)
+            })
+            .collect()
+    }
+
+    /// Bits of the output-gate index and of each input-wire index in a
+    /// selector entry. Skip connections add two bits to each input-wire
+    /// index, see `wire_tables`.
+    pub fn selector_widths(&self) -> (u32, u32) {
         let n_gates = self.gates.len();
-        assert!(n_gates > 0, "There must be at least one gate in the layer.");
 
-        if n_gates == 1 {
-            3
+        let (a_bits, bc_bits) = if n_gates == 1 {
+            (1, 1)
         } else {
-            let n_gates_log = n_gates.ilog2();
-            let n_bits = n_gates_log + 1;
-            n_gates_log + (n_bits * 2)
+            (n_gates.ilog2(), n_gates.ilog2() + 1)
+        };
+
+        match self.has_skip_connections() {
+            true => (a_bits, bc_bits + 2),
+            false => (a_bits, bc_bits),
         }
     }
 
+    /// Number of evaluations in this layer's add/mul selector polys, or
+    /// `usize::MAX` if that does not fit in a `usize`.
+    pub fn selector_size(&self) -> usize {
+        1usize
+            .checked_shl(self.get_bits_for_gates())
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Number of variables of this layer's fbc poly once the output-gate
+    /// variables are fixed, which is the number of sum-check rounds: the b
+    /// and c variables of the layer below.
+    pub fn num_sumcheck_rounds(&self) -> usize {
+        2 * self.selector_widths().1 as usize
+    }
+
+    fn get_bits_for_gates(&self) -> u32 {
+        assert!(
+            !self.gates.is_empty(),
+            "There must be at least one gate in the layer."
+        );
+
+        let (a_bits, bc_bits) = self.selector_widths();
+
+        a_bits + 2 * bc_bits
+    }
+
+    /// Wiring is positional: gate `idx` reads wires `2 * idx` and
+    /// `2 * idx + 1` of the layer below, or of the layer two below for the
+    /// right input of a skip connection, so no two gates share an input pair
+    /// and each gate owns its own selector entry even when gates compute the
+    /// same value. There are no shared sub-expressions to deduplicate.
     fn gate_to_bits(&self) -> Vec<usize> {
-        let n_gates = self.gates.len();
-        let n_gates_log = n_gates.ilog2();
+        let (_, bc_bits) = self.selector_widths();
 
-        let mut gate_decimal_values = Vec::new();
+        (0..self.gates.len())
+            .map(|idx| {
+                let (b, c) = self.input_wires(idx);
 
-        for (idx, _) in self.gates.iter().enumerate() {
-            let mut gate_binary_values = Vec::new();
+                (((idx << bc_bits) | b) << bc_bits) | c
+            })
+            .collect()
+    }
+}
 
-            gate_binary_values.push(idx);
-            gate_binary_values.push(2 * idx);
-            gate_binary_values.push(2 * idx + 1);
+/// Supplies a circuit's layers by index, bottom-up, so repetitive circuits
+/// can be described without materializing every layer.
+pub trait LayerSource<F: PrimeField> {
+    fn layer(&self, index: usize) -> Layer<F>;
 
-            let segments: Vec<(usize, u32)> = gate_binary_values
-                .iter()
-                .enumerate()
-                .map(|(i, val)| {
-                    if n_gates == 1 {
-                        (*val, 1)
-                    } else {
-                        if i == 0 {
-                            (*val, n_gates_log)
-                        } else {
-                            (*val, n_gates_log + 1)
-                        }
-                    }
-                })
-                .collect();
+    fn depth(&self) -> usize;
+}
 
-            let decimal_value = segments
-                .iter()
-                .fold(0, |acc, &(value, width)| (acc << width) | value);
+impl<F: PrimeField> LayerSource<F> for Vec<Layer<F>> {
+    fn layer(&self, index: usize) -> Layer<F> {
+        self[index].clone()
+    }
+
+    fn depth(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Every layer's values on one set of inputs, as `Circuit::evaluate`
+/// computes them. Layers are indexed bottom-up like `Circuit::layers`.
+/// `try_new` only accepts layers each half the width of the layer below it,
+/// the inputs for layer 0; `evaluate` keeps whatever widths the circuit has.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitTrace<F: PrimeField> {
+    inputs: Vec<F>,
+    layers: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> CircuitTrace<F> {
+    pub fn try_new(inputs: Vec<F>, layers: Vec<Vec<F>>) -> Result<Self, CircuitError> {
+        if layers.is_empty() {
+            return Err(CircuitError::EmptyTrace);
+        }
+
+        let mut width_below = inputs.len();
+        for layer in layers.iter() {
+            if 2 * layer.len() != width_below {
+                return Err(CircuitError::WidthMismatch {
+                    output: width_below,
+                    input: 2 * layer.len(),
+                });
+            }
+
+            width_below = layer.len();
+        }
+
+        Ok(Self { inputs, layers })
+    }
+
+    pub fn inputs(&self) -> &[F] {
+        &self.inputs
+    }
+
+    pub fn output(&self) -> &[F] {
+        self.layers.last().unwrap()
+    }
+
+    pub fn layer(&self, index: usize) -> &[F] {
+        &self.layers[index]
+    }
+
+    pub fn layers(&self) -> &[Vec<F>] {
+        &self.layers
+    }
+
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Values feeding the layer at top-down index `idx`, like
+    /// `gkr_protocol::layer_below`: the inputs for the bottom layer,
+    /// otherwise the next layer down.
+    pub fn layer_below(&self, idx: usize) -> &[F] {
+        let depth = self.depth();
+
+        if idx >= depth {
+            panic!("layer index {idx} out of bounds for {depth} layers");
+        }
 
-            gate_decimal_values.push(decimal_value);
+        match idx == depth - 1 {
+            true => &self.inputs,
+            false => &self.layers[depth - idx - 2],
         }
+    }
+}
 
-        gate_decimal_values
+impl<F: PrimeField> Index<usize> for CircuitTrace<F> {
+    type Output = [F];
+
+    fn index(&self, index: usize) -> &[F] {
+        self.layer(index)
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    WidthMismatch {
+        output: usize,
+        input: usize,
+    },
+    InvalidSkipConnection {
+        layer: usize,
+        gate: usize,
+    },
+    /// The top `count` layers cannot stand alone: `count` is zero or past
+    /// the depth, or the lowest of them reads a skip connection from below
+    /// the split.
+    InvalidSplit {
+        count: usize,
+    },
+    EmptyTrace,
+}
+
+/// Why `Circuit::evaluate_bytes` could not read its inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// `inputs[index]` is not the canonical encoding of a field element.
+    NonCanonical { index: usize },
+    /// There are `actual` inputs where the circuit reads `expected`.
+    WrongLength { expected: usize, actual: usize },
+}
+
 #[derive(Debug, Clone)]
-pub struct Circuit<F: PrimeField> {
-    pub layers: Vec<Layer<F>>,
+pub struct Circuit<F: PrimeField, S = Vec<Layer<F>>> {
+    pub layers: S,
+    #[cfg(feature = "lookup")]
+    pub tables: Arc<Vec<Vec<F>>>,
+    pub gate_fns: Arc<Vec<Arc<dyn GateFn<F>>>>,
+    _field: PhantomData<F>,
 }
 
 impl<F: PrimeField> Circuit<F> {
@@ -136,68 +652,1267 @@ impl<F: PrimeField> Circuit<F> {
                 Layer::new(gates)
             })
             .collect();
-        Self { layers }
+        Self::from_source(layers)
+    }
+
+    pub fn with_constants(structure: Vec<Vec<Operation>>, constants: Vec<Option<Vec<F>>>) -> Self {
+        if structure.len() != constants.len() {
+            panic!("there must be one constants entry per layer");
+        }
+
+        let layers = structure
+            .into_iter()
+            .zip(constants)
+            .map(|(ops_layer, layer_constants)| {
+                let gates = ops_layer
+                    .into_iter()
+                    .map(|op| Gate::new(F::zero(), F::zero(), op))
+                    .collect();
+
+                match layer_constants {
+                    Some(layer_constants) => Layer::with_constants(gates, layer_constants),
+                    None => Layer::new(gates),
+                }
+            })
+            .collect();
+        Self::from_source(layers)
+    }
+
+    /// Adds a layer on top reading the current top layer's outputs, so it
+    /// needs half as many gates as the top layer has. Any non-empty layer
+    /// can start an empty circuit.
+    pub fn push_layer(&mut self, layer: Vec<Operation>) -> Result<(), CircuitError> {
+        let output = self.layers.last().map_or(0, |top| top.gates.len());
+        let input = layer.len() * 2;
+
+        if input == 0 || (output != 0 && output != input) {
+            return Err(CircuitError::WidthMismatch { output, input });
+        }
+
+        let gates = layer
+            .into_iter()
+            .map(|op| Gate::new(F::zero(), F::zero(), op))
+            .collect();
+        self.layers.push(Layer::new(gates));
+
+        Ok(())
+    }
+
+    /// Makes gate `gate` of layer `layer` (bottom-up) read its right input
+    /// from wire `2 * gate + 1` of the layer two below, the inputs for layer
+    /// 1, instead of from the layer directly below. The layer two below must
+    /// be twice as wide as the layer below, as positional wiring makes it.
+    pub fn add_skip_connection(&mut self, layer: usize, gate: usize) -> Result<(), CircuitError> {
+        let error = CircuitError::InvalidSkipConnection { layer, gate };
+
+        if layer == 0 || layer >= self.layers.len() || gate >= self.layers[layer].gates.len() {
+            return Err(error);
+        }
+
+        let two_below_width = match layer {
+            1 => self.input_width(),
+            _ => self.layers[layer - 2].gates.len(),
+        };
+
+        if two_below_width != 4 * self.layers[layer].gates.len() {
+            return Err(error);
+        }
+
+        let skips = &mut self.layers[layer].skip_connections;
+        if !skips.contains(&gate) {
+            skips.push(gate);
+            skips.sort_unstable();
+        }
+
+        Ok(())
+    }
+
+    /// Registers a public 2- or 4-entry table for `Operation::Lookup` gates
+    /// and returns its id.
+    #[cfg(feature = "lookup")]
+    pub fn register_table(&mut self, table: Vec<F>) -> usize {
+        if table.len() != 2 && table.len() != 4 {
+            panic!("lookup tables must have 2 or 4 entries");
+        }
+
+        Arc::make_mut(&mut self.tables).push(table);
+        self.tables.len() - 1
+    }
+
+    /// Registers a gate function for `Operation::Custom` gates and returns
+    /// its id. Its degree must be the highest power of either input among
+    /// its terms, since that is the round degree the verifier expects.
+    pub fn register_gate(&mut self, gate: Arc<dyn GateFn<F>>) -> usize {
+        let highest_power = gate
+            .selector_contribution()
+            .iter()
+            .map(|(_, left, right)| *left.max(right))
+            .max()
+            .unwrap_or(0);
+
+        if gate.degree() != highest_power {
+            panic!("a gate's degree must be the highest power of either input in its terms");
+        }
+
+        Arc::make_mut(&mut self.gate_fns).push(gate);
+        self.gate_fns.len() - 1
     }
 
-    pub fn evaluate(&mut self, inputs: &[F]) -> Vec<Vec<F>> {
+    pub fn evaluate(&mut self, inputs: &[F]) -> CircuitTrace<F> {
         let mut result = Vec::new();
         let mut current_inputs = inputs.to_vec();
+        let mut inputs_two_below = Vec::new();
+
+        for index in 0..self.layers.len() {
+            let outputs = self
+                .layer(index)
+                .evaluate_with_skips(&current_inputs, &inputs_two_below);
+            let layer = &mut self.layers[index];
 
-        for layer in &mut self.layers {
-            for (gate, input_pair) in layer.gates.iter_mut().zip(current_inputs.chunks_exact(2)) {
-                let (l_input, r_input) = (input_pair[0], input_pair[1]);
-                gate.l_input = l_input;
-                gate.r_input = r_input;
-                gate.output = gate.op.apply(l_input, r_input);
+            for (idx, (gate, output)) in layer.gates.iter_mut().zip(outputs).enumerate() {
+                gate.l_input = current_inputs[2 * idx];
+                gate.r_input = match layer.skip_connections.contains(&idx) {
+                    true => inputs_two_below[2 * idx + 1],
+                    false => current_inputs[2 * idx + 1],
+                };
+                gate.output = output;
             }
             let layer_outputs = layer.get_layer_poly();
             result.push(layer_outputs.clone());
-            current_inputs = layer_outputs;
+            inputs_two_below = std::mem::replace(&mut current_inputs, layer_outputs);
         }
-        result
+
+        CircuitTrace {
+            inputs: inputs.to_vec(),
+            layers: result,
+        }
+    }
+
+    /// `evaluate` on inputs in the canonical encoding `field_vec_to_bytes`
+    /// writes, each taking the whole 32 bytes and reduced below the modulus,
+    /// one per input wire.
+    pub fn evaluate_bytes(&mut self, inputs: &[[u8; 32]]) -> Result<CircuitTrace<F>, ParseError> {
+        if inputs.len() != self.input_width() {
+            return Err(ParseError::WrongLength {
+                expected: self.input_width(),
+                actual: inputs.len(),
+            });
+        }
+
+        let inputs = inputs
+            .iter()
+            .enumerate()
+            .map(|(index, bytes)| {
+                let mut reader = bytes.as_slice();
+
+                match F::deserialize_compressed(&mut reader) {
+                    Ok(input) if reader.is_empty() => Ok(input),
+                    _ => Err(ParseError::NonCanonical { index }),
+                }
+            })
+            .collect::<Result<Vec<F>, ParseError>>()?;
+
+        Ok(self.evaluate(&inputs))
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::{Circuit, Gate, Layer, Operation};
-    use ark_bn254::Fq;
+impl<F: PrimeField, S: LayerSource<F>> Circuit<F, S> {
+    pub fn from_source(layers: S) -> Self {
+        Self {
+            layers,
+            #[cfg(feature = "lookup")]
+            tables: Arc::default(),
+            gate_fns: Arc::default(),
+            _field: PhantomData,
+        }
+    }
 
-    #[test]
-    fn it_evaluates_the_circuit_correctly() {
-        let structure: Vec<Vec<Operation>> = vec![
-            vec![
-                Operation::Mul,
-                Operation::Mul,
-                Operation::Mul,
-                Operation::Mul,
-            ],
-            vec![Operation::Add, Operation::Add],
-            vec![Operation::Add],
-        ];
+    /// Layer `index` bottom-up, sharing the circuit's tables and gate
+    /// functions rather than copying them.
+    pub fn layer(&self, index: usize) -> Layer<F> {
+        let layer = Layer {
+            gate_fns: self.gate_fns.clone(),
+            ..self.layers.layer(index)
+        };
 
-        let inputs: Vec<Fq> = vec![
-            Fq::from(5),
-            Fq::from(2),
-            Fq::from(2),
-            Fq::from(4),
-            Fq::from(10),
-            Fq::from(0),
-            Fq::from(3),
-            Fq::from(3),
-        ];
+        #[cfg(feature = "lookup")]
+        let layer = Layer {
+            tables: self.tables.clone(),
+            ..layer
+        };
 
-        let expected_evaluations: Vec<Vec<Fq>> = vec![
-            vec![Fq::from(10), Fq::from(8), Fq::from(0), Fq::from(9)],
-            vec![Fq::from(18), Fq::from(9)],
-            vec![Fq::from(27)],
-        ];
+        layer
+    }
 
-        let mut circuit = Circuit::new(structure);
+    pub fn depth(&self) -> usize {
+        self.layers.depth()
+    }
 
-        let evaluations = circuit.evaluate(&inputs);
+    /// Layers from the output layer down to the input layer, the order the
+    /// protocol walks them in.
+    pub fn layers_top_down(&self) -> impl Iterator<Item = Layer<F>> + '_ {
+        (0..self.depth()).rev().map(|index| self.layer(index))
+    }
+
+    pub fn input_width(&self) -> usize {
+        self.layer(0).gates.len() * 2
+    }
+
+    /// Whether every layer has exactly half the gates of the layer below and
+    /// the output layer a single gate, the shape the protocol assumes.
+    pub fn is_balanced_binary(&self) -> bool {
+        let widths: Vec<usize> = (0..self.depth())
+            .map(|index| self.layer(index).gates.len())
+            .collect();
+
+        widths.last() == Some(&1) && widths.windows(2).all(|pair| pair[0] == 2 * pair[1])
+    }
+
+    pub fn probabilistic_equiv<T: LayerSource<F>>(
+        &self,
+        other: &Circuit<F, T>,
+        rng: &mut impl Rng,
+        trials: usize,
+    ) -> bool {
+        if self.input_width() != other.input_width() {
+            return false;
+        }
+
+        (0..trials).all(|_| {
+            let inputs: Vec<F> = (0..self.input_width()).map(|_| F::rand(rng)).collect();
+
+            self.evaluate_layer(&inputs, self.depth() - 1)
+                == other.evaluate_layer(&inputs, other.depth() - 1)
+        })
+    }
+
+    /// The output layer as the padded multilinear poly `prove` commits to,
+    /// holding only one layer's values at a time.
+    pub fn output_poly(&self, inputs: &[F]) -> MultilinearPoly<F> {
+        output_layer_poly(self.evaluate_layer(inputs, self.depth() - 1))
+    }
+
+    /// The output layer's relation `add_i(a, b, c) * (w(b) + w(c)) +
+    /// mul_i(a, b, c) * (w(b) * w(c))` over all of its selector variables,
+    /// summing to the sum of the circuit's outputs over the hypercube. The
+    /// layers below enter through `w`, the values they evaluate to on
+    /// `inputs`, since their own wiring cannot be nested into one multilinear
+    /// sum. Its tables grow with the cube of the output width, so this is
+    /// only meant for inspecting small circuits.
+    pub fn as_relation_poly(&self, inputs: &[F]) -> SumPoly<F> {
+        let top = self.depth() - 1;
+        let layer = self.layer(top);
+        let (a_bits, bc_bits) = layer.selector_widths();
+
+        let inputs_below = match top {
+            0 => inputs.to_vec(),
+            _ => self.evaluate_layer(inputs, top - 1),
+        };
+        let inputs_two_below = match top {
+            0 => Vec::new(),
+            1 => inputs.to_vec(),
+            _ => self.evaluate_layer(inputs, top - 2),
+        };
+
+        let (mut w_b, mut w_c) = layer.wire_tables(&inputs_below, &inputs_two_below);
+        w_b.resize(1 << bc_bits, F::zero());
+        w_c.resize(1 << bc_bits, F::zero());
+
+        let ones = vec![F::one(); 1 << a_bits];
+        let over_a = |op| {
+            let wires = tensor_add_mul_polynomials(&w_b, &w_c, op);
+            tensor_add_mul_polynomials(&ones, &wires.evaluation, Operation::Mul).evaluation
+        };
+
+        let (sum_selector, product_selector) = layer.get_fbc_selectors();
+        let terms = vec![
+            ProductPoly::new(vec![sum_selector.evaluation, over_a(Operation::Add)]),
+            ProductPoly::new(vec![product_selector.evaluation, over_a(Operation::Mul)]),
+        ];
+
+        #[cfg(feature = "lookup")]
+        let terms = if layer.has_lookups() {
+            let mut terms = terms;
+            let w_ones = vec![F::one(); w_b.len()];
+            let [constant_i, left_i, right_i] = layer.get_lookup_selectors();
+
+            let w_b = tensor_add_mul_polynomials(&w_b, &w_ones, Operation::Mul).evaluation;
+            let w_c = tensor_add_mul_polynomials(&w_ones, &w_c, Operation::Mul).evaluation;
+
+            terms.push(ProductPoly::new(vec![
+                constant_i.evaluation,
+                vec![F::one(); ones.len() * w_b.len()],
+            ]));
+            terms.push(ProductPoly::new(vec![
+                left_i.evaluation,
+                tensor_add_mul_polynomials(&ones, &w_b, Operation::Mul).evaluation,
+            ]));
+            terms.push(ProductPoly::new(vec![
+                right_i.evaluation,
+                tensor_add_mul_polynomials(&ones, &w_c, Operation::Mul).evaluation,
+            ]));
+
+            terms
+        } else {
+            terms
+        };
+
+        let mut terms = terms;
+        for (left, right, selector) in layer.get_custom_selectors() {
+            let mut factors = vec![selector.evaluation];
+            factors.extend(
+                monomial_wire_factors(&w_b, &w_c, left, right)
+                    .into_iter()
+                    .map(|factor| {
+                        tensor_add_mul_polynomials(&ones, &factor, Operation::Mul).evaluation
+                    }),
+            );
+
+            terms.push(ProductPoly::new(factors));
+        }
+
+        SumPoly::new(pad_factors(terms, layer.round_degree()))
+    }
+
+    /// `self` followed by `other`, which reads `self`'s outputs as its inputs.
+    ///
+    /// There is no `repeat` stacking a circuit on itself: positional wiring
+    /// halves the width at every layer, so no circuit's outputs are as wide
+    /// as its inputs and a copy can never follow another. Repetition would
+    /// need layers that widen their input, which the wiring cannot express.
+    pub fn then<T: LayerSource<F>>(
+        &self,
+        other: &Circuit<F, T>,
+    ) -> Result<Circuit<F>, CircuitError> {
+        let output = self.layer(self.depth() - 1).gates.len();
+
+        if output != other.input_width() {
+            return Err(CircuitError::WidthMismatch {
+                output,
+                input: other.input_width(),
+            });
+        }
+
+        let mut layers: Vec<Layer<F>> = (0..self.depth())
+            .map(|index| self.layer(index))
+            .chain((0..other.depth()).map(|index| other.layer(index)))
+            .collect();
+
+        let gate_offset = self.gate_fns.len();
+        #[cfg(feature = "lookup")]
+        let table_offset = self.tables.len();
+
+        for layer in &mut layers[self.depth()..] {
+            for gate in &mut layer.gates {
+                match &mut gate.op {
+                    Operation::Custom(id) => *id += gate_offset,
+                    #[cfg(feature = "lookup")]
+                    Operation::Lookup(id) => *id += table_offset,
+                    _ => {}
+                }
+            }
+        }
+
+        let circuit = Circuit {
+            gate_fns: Arc::new(
+                self.gate_fns
+                    .iter()
+                    .chain(other.gate_fns.iter())
+                    .cloned()
+                    .collect(),
+            ),
+            ..Circuit::from_source(layers)
+        };
+
+        #[cfg(feature = "lookup")]
+        let circuit = Circuit {
+            tables: Arc::new(
+                self.tables
+                    .iter()
+                    .chain(other.tables.iter())
+                    .cloned()
+                    .collect(),
+            ),
+            ..circuit
+        };
+
+        Ok(circuit)
+    }
+
+    /// The top `count` layers as a circuit of their own, reading the outputs
+    /// of the layer below them as its inputs.
+    pub fn top_layers(&self, count: usize) -> Result<Circuit<F>, CircuitError> {
+        if count == 0 || count > self.depth() {
+            return Err(CircuitError::InvalidSplit { count });
+        }
+
+        let bottom = self.layer(self.depth() - count);
+
+        if bottom.has_skip_connections() {
+            return Err(CircuitError::InvalidSplit { count });
+        }
+
+        let layers = std::iter::once(bottom)
+            .chain((self.depth() - count + 1..self.depth()).map(|index| self.layer(index)))
+            .collect();
+
+        Ok(self.with_layers(layers))
+    }
+
+    /// The bottom `count` layers as a circuit of their own, reading the
+    /// circuit's inputs and outputting what the layer above them reads.
+    pub fn bottom_layers(&self, count: usize) -> Result<Circuit<F>, CircuitError> {
+        if count == 0 || count > self.depth() {
+            return Err(CircuitError::InvalidSplit { count });
+        }
+
+        Ok(self.with_layers((0..count).map(|index| self.layer(index)).collect()))
+    }
+
+    fn with_layers(&self, layers: Vec<Layer<F>>) -> Circuit<F> {
+        let circuit = Circuit {
+            gate_fns: self.gate_fns.clone(),
+            ..Circuit::from_source(layers)
+        };
+
+        #[cfg(feature = "lookup")]
+        let circuit = Circuit {
+            tables: self.tables.clone(),
+            ..circuit
+        };
+
+        circuit
+    }
+
+    /// A union bound on the chance a false claim survives verification over a
+    /// field of `field_bits` bits: `d / |F|` per sum-check round of a layer
+    /// with round degree `d`, `1 / |F|` per alpha/beta claim fold and
+    /// `v / |F|` for the output poly in `v` variables evaluated at a random
+    /// point.
+    pub fn soundness_error(&self, field_bits: usize) -> f64 {
+        let output_width = self.layer(self.depth() - 1).gates.len();
+        let output_vars = (output_width.next_power_of_two().ilog2() as usize).max(1);
+
+        let sum_check_events: usize = self
+            .layers_top_down()
+            .map(|layer| layer.num_sumcheck_rounds() * layer.round_degree())
+            .sum();
+        let folds = self.depth() - 1;
+
+        let bad_events = sum_check_events + folds + output_vars;
+
+        bad_events as f64 / 2f64.powf(field_bits as f64)
+    }
+
+    /// The fewest field bits for which `soundness_error` is at most
+    /// `target_error`, which must be positive and finite.
+    pub fn min_field_bits(&self, target_error: f64) -> usize {
+        if !target_error.is_finite() || target_error <= 0.0 {
+            panic!("the target soundness error must be positive and finite");
+        }
+
+        (0..=MAX_FIELD_BITS)
+            .find(|bits| self.soundness_error(*bits) <= target_error)
+            .expect("the error rounds to zero well before MAX_FIELD_BITS")
+    }
+
+    /// Input indices no output depends on. Positional wiring reads every
+    /// input, so an input only goes unused when each gate above it that
+    /// reaches it ignores it, see `Layer::reads_inputs`.
+    pub fn unused_inputs(&self) -> Vec<usize> {
+        let mut live = vec![true; self.layer(self.depth() - 1).gates.len()];
+        // Wires read by skip connections, for the layer below and the one
+        // below that.
+        let mut skipped_to: Vec<bool> = Vec::new();
+        let mut skipped_to_next: Vec<bool> = Vec::new();
+
+        for layer in self.layers_top_down() {
+            for (live, skipped) in live.iter_mut().zip(&skipped_to) {
+                *live |= *skipped;
+            }
+
+            let mut live_below = vec![false; layer.gates.len() * 2];
+            let mut live_two_below = vec![false; layer.gates.len() * 4];
+
+            for idx in 0..layer.gates.len() {
+                if live[idx] {
+                    let (left, right) = layer.reads_inputs(idx);
+                    live_below[2 * idx] = left;
+
+                    match layer.is_skip_connection(idx) {
+                        true => live_two_below[2 * idx + 1] = right,
+                        false => live_below[2 * idx + 1] = right,
+                    }
+                }
+            }
+
+            skipped_to = std::mem::replace(&mut skipped_to_next, live_two_below);
+            live = live_below;
+        }
+
+        for (live, skipped) in live.iter_mut().zip(&skipped_to) {
+            *live |= *skipped;
+        }
+
+        (0..live.len()).filter(|idx| !live[*idx]).collect()
+    }
+
+    pub fn evaluate_layer(&self, inputs: &[F], layer_index: usize) -> Vec<F> {
+        let mut current_inputs = inputs.to_vec();
+        let mut inputs_two_below = Vec::new();
+
+        for index in 0..=layer_index {
+            let outputs = self
+                .layer(index)
+                .evaluate_with_skips(&current_inputs, &inputs_two_below);
+            inputs_two_below = std::mem::replace(&mut current_inputs, outputs);
+        }
+
+        current_inputs
+    }
+
+    /// One line per layer from the output down, listing each gate's
+    /// operation, then the input width. Gates with a constant other than one
+    /// show it before the operation.
+    pub fn render(&self) -> String {
+        let mut rendered = String::new();
+
+        for index in (0..self.depth()).rev() {
+            let layer = self.layer(index);
+            let gates: Vec<String> = layer
+                .gates
+                .iter()
+                .enumerate()
+                .map(|(idx, gate)| match layer.gate_constant(idx) {
+                    constant if constant.is_one() => format!("[{}]", gate.op.symbol()),
+                    constant => format!("[{constant}{}]", gate.op.symbol()),
+                })
+                .collect();
+
+            writeln!(
+                rendered,
+                "layer {index} (width {}): {}",
+                layer.gates.len(),
+                gates.join(" ")
+            )
+            .expect("writing to a String cannot fail");
+        }
+
+        write!(rendered, "inputs (width {})", self.input_width())
+            .expect("writing to a String cannot fail");
+
+        rendered
+    }
+}
+
+impl<F: PrimeField, S: LayerSource<F>> fmt::Display for Circuit<F, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+/// Pads the output layer to at least one variable, since the protocol always
+/// draws an output challenge.
+pub(crate) fn output_layer_poly<F: PrimeField>(w_0: Vec<F>) -> MultilinearPoly<F> {
+    let output_vars = (w_0.len().ilog2() as usize).max(1);
+
+    MultilinearPoly::with_num_vars(w_0, output_vars)
+}
+
+/// The table of `op` on every pair of entries of `poly_a` and `poly_b`, with
+/// `poly_b` varying fastest. Batching the mul rows' multiplications measured
+/// no faster, 1.76 ms against 1.74 ms for a 2^16 row (the `extend_scaled`
+/// benches of multilinear_polynomial), so the loop stays scalar.
+pub fn tensor_add_mul_polynomials<F: PrimeField>(
+    poly_a: &[F],
+    poly_b: &[F],
+    op: Operation,
+) -> MultilinearPoly<F> {
+    let new_eval: Vec<F> = poly_a
+        .iter()
+        .flat_map(|a| poly_b.iter().map(move |b| op.apply(*a, *b)))
+        .collect();
+
+    MultilinearPoly::new(new_eval)
+}
+
+/// The w-factors of a `w_b^left * w_c^right` term over the b and c
+/// variables. Each `w_b * w_c` pair shares one multilinear factor since b and
+/// c are disjoint, so there are `max(left, right)` factors, or a single
+/// all-ones factor for a constant term.
+pub(crate) fn monomial_wire_factors<F: PrimeField>(
+    w_b: &[F],
+    w_c: &[F],
+    left: usize,
+    right: usize,
+) -> Vec<Vec<F>> {
+    let ones = vec![F::one(); w_b.len()];
+    let shared = left.min(right);
+
+    let mut factors: Vec<Vec<F>> = (0..shared)
+        .map(|_| tensor_add_mul_polynomials(w_b, w_c, Operation::Mul).evaluation)
+        .collect();
+    factors.extend(
+        (shared..left).map(|_| tensor_add_mul_polynomials(w_b, &ones, Operation::Mul).evaluation),
+    );
+    factors.extend(
+        (shared..right).map(|_| tensor_add_mul_polynomials(&ones, w_c, Operation::Mul).evaluation),
+    );
+
+    if factors.is_empty() {
+        factors.push(vec![F::one(); w_b.len() * w_c.len()]);
+    }
+
+    factors
+}
+
+/// `terms` with all-ones factors appended until each has `num_factors`, so
+/// terms of different degrees fit in one `SumPoly`.
+pub(crate) fn pad_factors<F: PrimeField>(
+    terms: Vec<ProductPoly<F>>,
+    num_factors: usize,
+) -> Vec<ProductPoly<F>> {
+    terms
+        .into_iter()
+        .map(|mut term| {
+            let ones = MultilinearPoly::new(vec![F::one(); term.evaluation[0].evaluation.len()]);
+            let missing = num_factors.saturating_sub(term.evaluation.len());
+
+            term.evaluation.extend(std::iter::repeat_n(ones, missing));
+            term
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        tensor_add_mul_polynomials, Circuit, CircuitError, CircuitTrace, Gate, Layer, Operation,
+        ParseError,
+    };
+    use crate::{
+        gkr_protocol::prove,
+        test_util::{depth_1_circuit_and_inputs, sample_circuit_and_inputs, SquarePlus},
+    };
+    use ark_bn254::Fq;
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_std::test_rng;
+    use fiat_shamir::fiat_shamir_transcript::field_vec_to_bytes;
+    use std::sync::Arc;
+
+    #[test]
+    fn it_add_polys_correctly() {
+        let poly_a = &[Fq::from(0), Fq::from(2)];
+        let poly_b = &[Fq::from(0), Fq::from(3)];
+
+        let expected_poly = vec![Fq::from(0), Fq::from(3), Fq::from(2), Fq::from(5)];
+
+        let result = tensor_add_mul_polynomials(poly_a, poly_b, Operation::Add);
+
+        assert_eq!(result.evaluation, expected_poly);
+
+        let poly_a = &[Fq::from(0), Fq::from(3)];
+        let poly_b = &[Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(2)];
+
+        let expected_poly = vec![
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(2),
+            Fq::from(3),
+            Fq::from(3),
+            Fq::from(3),
+            Fq::from(5),
+        ];
+
+        let result = tensor_add_mul_polynomials(poly_a, poly_b, Operation::Add);
+
+        assert_eq!(result.evaluation, expected_poly);
+    }
+
+    #[test]
+    fn it_multiplies_polys_correctly() {
+        let poly_a = &[Fq::from(0), Fq::from(2)];
+        let poly_b = &[Fq::from(0), Fq::from(3)];
+
+        let expected_poly = vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(6)];
+
+        let result = tensor_add_mul_polynomials(poly_a, poly_b, Operation::Mul);
+
+        assert_eq!(result.evaluation, expected_poly);
+
+        let poly_a = &[Fq::from(0), Fq::from(3)];
+        let poly_b = &[Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(2)];
+
+        let expected_poly = vec![
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(0),
+            Fq::from(6),
+        ];
+
+        let result = tensor_add_mul_polynomials(poly_a, poly_b, Operation::Mul);
+
+        assert_eq!(result.evaluation, expected_poly);
+    }
+
+    #[test]
+    fn it_evaluates_the_circuit_correctly() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let expected_evaluations: Vec<Vec<Fq>> = vec![
+            vec![Fq::from(10), Fq::from(8), Fq::from(0), Fq::from(9)],
+            vec![Fq::from(18), Fq::from(9)],
+            vec![Fq::from(27)],
+        ];
+
+        let evaluations = circuit.evaluate(&inputs);
+
+        assert_eq!(evaluations.layers(), expected_evaluations);
+
+        for (layer_index, expected) in expected_evaluations.iter().enumerate() {
+            assert_eq!(&circuit.evaluate_layer(&inputs, layer_index), expected);
+        }
+    }
+
+    #[test]
+    fn it_evaluates_inputs_given_as_bytes() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let bytes: Vec<[u8; 32]> = inputs
+            .iter()
+            .map(|input| field_vec_to_bytes(&[*input]).try_into().unwrap())
+            .collect();
+
+        assert_eq!(
+            circuit.evaluate_bytes(&bytes),
+            Ok(circuit.evaluate(&inputs))
+        );
+
+        let mut non_canonical = bytes.clone();
+        non_canonical[3] = Fq::MODULUS.to_bytes_le().try_into().unwrap();
+        assert_eq!(
+            circuit.evaluate_bytes(&non_canonical),
+            Err(ParseError::NonCanonical { index: 3 })
+        );
+
+        assert_eq!(
+            circuit.evaluate_bytes(&bytes[..6]),
+            Err(ParseError::WrongLength {
+                expected: 8,
+                actual: 6
+            })
+        );
+        assert_eq!(
+            circuit.evaluate_bytes(&[bytes.clone(), bytes].concat()),
+            Err(ParseError::WrongLength {
+                expected: 8,
+                actual: 16
+            })
+        );
+    }
+
+    #[test]
+    fn it_traces_layers_halving_in_width() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let trace = circuit.evaluate(&inputs);
+
+        assert_eq!(trace.depth(), circuit.depth());
+        assert_eq!(trace.inputs(), inputs);
+        assert_eq!(trace.output(), [Fq::from(27)]);
+
+        let mut width_below = trace.inputs().len();
+        for index in 0..trace.depth() {
+            assert_eq!(2 * trace.layer(index).len(), width_below);
+            width_below = trace.layer(index).len();
+        }
+
+        assert_eq!(trace.layer_below(0), trace.layer(1));
+        assert_eq!(trace.layer_below(2), trace.inputs());
+    }
+
+    #[test]
+    fn it_rejects_traces_not_halving_in_width() {
+        assert_eq!(
+            CircuitTrace::try_new(
+                vec![Fq::from(1); 8],
+                vec![vec![Fq::from(1); 4], vec![Fq::from(1); 3]],
+            ),
+            Err(CircuitError::WidthMismatch {
+                output: 4,
+                input: 6
+            })
+        );
+        assert_eq!(
+            CircuitTrace::<Fq>::try_new(vec![Fq::from(1); 2], Vec::new()),
+            Err(CircuitError::EmptyTrace)
+        );
+    }
+
+    #[test]
+    fn it_evaluates_layers_wider_than_their_inputs() {
+        let mut circuit = Circuit::<Fq>::new(vec![vec![Operation::Mul; 2], vec![Operation::Add]]);
+
+        let trace = circuit.evaluate(&[Fq::from(2), Fq::from(3)]);
+
+        assert_eq!(trace.layer(0), [Fq::from(6), Fq::from(0)]);
+        assert_eq!(trace.output(), [Fq::from(6)]);
+    }
+
+    #[test]
+    fn it_detects_balanced_binary_circuits() {
+        let (circuit, _) = sample_circuit_and_inputs();
+        assert!(circuit.is_balanced_binary());
+
+        let wide_output =
+            Circuit::<Fq>::new(vec![vec![Operation::Mul; 4], vec![Operation::Add; 2]]);
+        assert!(!wide_output.is_balanced_binary());
+
+        let uneven = Circuit::<Fq>::new(vec![
+            vec![Operation::Mul; 4],
+            vec![Operation::Add; 3],
+            vec![Operation::Add],
+        ]);
+        assert!(!uneven.is_balanced_binary());
+    }
+
+    #[test]
+    fn it_evaluates_single_gate_contributions() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let evaluations = circuit.evaluate(&inputs);
+
+        for (index, outputs) in evaluations.layers().iter().enumerate() {
+            let below = match index {
+                0 => &inputs,
+                _ => &evaluations[index - 1],
+            };
+            let layer = circuit.layer(index);
+
+            for (gate, output) in outputs.iter().enumerate() {
+                assert_eq!(layer.gate_contribution(gate, below), *output);
+            }
+        }
+
+        assert_eq!(
+            circuit.layer(2).gate_contribution(0, &evaluations[1]),
+            Fq::from(27)
+        );
+    }
+
+    #[test]
+    fn it_sums_the_relation_poly_to_the_output() {
+        let (circuit, inputs) = depth_1_circuit_and_inputs();
+        let relation = circuit.as_relation_poly(&inputs);
+
+        assert_eq!(relation.hypercube_sum(), Fq::from(7));
+        assert_eq!(relation.polys[0].evaluation[0].num_of_vars, 3);
+
+        let (circuit, inputs) = sample_circuit_and_inputs();
+        assert_eq!(
+            circuit.as_relation_poly(&inputs).hypercube_sum(),
+            Fq::from(27)
+        );
+
+        #[cfg(feature = "lookup")]
+        {
+            let mut circuit = Circuit::<Fq>::new(vec![vec![Operation::Lookup(0)]]);
+            circuit.register_table(vec![Fq::from(3), Fq::from(5)]);
+
+            let relation = circuit.as_relation_poly(&[Fq::from(1), Fq::from(0)]);
+            assert_eq!(relation.hypercube_sum(), Fq::from(5));
+        }
+    }
+
+    #[test]
+    fn it_composes_custom_gates_with_their_round_degree() {
+        let mut bottom = Circuit::<Fq>::new(vec![vec![Operation::Custom(0); 2]]);
+        bottom.register_gate(Arc::new(SquarePlus { scale: 1 }));
+        let mut top = Circuit::<Fq>::new(vec![vec![Operation::Custom(0)]]);
+        top.register_gate(Arc::new(SquarePlus { scale: 2 }));
+
+        let relation = top.as_relation_poly(&[Fq::from(3), Fq::from(4)]);
+        assert_eq!(relation.hypercube_sum(), Fq::from(17));
+        assert_eq!(relation.round_degree(), 3);
+
+        let mut composed = bottom.then(&top).unwrap();
+        assert_eq!(composed.gate_fns.len(), 2);
+        assert!(Arc::ptr_eq(
+            &composed.layer(0).gate_fns,
+            &composed.layer(1).gate_fns
+        ));
+        assert_eq!(composed.layers[1].gates[0].op, Operation::Custom(1));
+        assert_eq!(composed.layer(0).round_degree(), 3);
+        assert!(composed.render().starts_with("layer 1 (width 1): [G1]"));
+
+        let evaluations = composed.evaluate(&[1, 2, 3, 4].map(Fq::from));
+        assert_eq!(evaluations[1], vec![Fq::from(35)]);
+
+        let plain = Circuit::<Fq>::new(vec![vec![Operation::Add; 2], vec![Operation::Add]]);
+        assert!(composed.soundness_error(64) > plain.soundness_error(64));
+    }
+
+    #[test]
+    fn it_evaluates_skip_connections_from_two_layers_below() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        for (layer, gate) in [(0, 0), (2, 1), (3, 0)] {
+            assert_eq!(
+                circuit.add_skip_connection(layer, gate),
+                Err(CircuitError::InvalidSkipConnection { layer, gate })
+            );
+        }
+
+        circuit.add_skip_connection(1, 1).unwrap();
+        assert_eq!(circuit.layers[1].input_wires(1), (2, 11));
+        assert_eq!(circuit.layer(1).selector_widths(), (1, 4));
+
+        let evaluations = circuit.evaluate(&inputs);
+        assert_eq!(evaluations[1], vec![Fq::from(18), Fq::from(4)]);
+        assert_eq!(circuit.layers[1].gates[1].r_input, Fq::from(4));
+        assert_eq!(circuit.unused_inputs(), vec![6, 7]);
+
+        circuit.add_skip_connection(2, 0).unwrap();
+        assert_eq!(circuit.evaluate_layer(&inputs, 2), vec![Fq::from(26)]);
+        assert_eq!(circuit.unused_inputs(), vec![4, 5, 6, 7]);
+        assert_eq!(
+            circuit.as_relation_poly(&inputs).hypercube_sum(),
+            Fq::from(26)
+        );
+    }
+
+    #[test]
+    fn it_renders_the_circuit_layer_by_layer() {
+        let (circuit, _) = sample_circuit_and_inputs();
+
+        assert_eq!(
+            circuit.render(),
+            "layer 2 (width 1): [+]\n\
+             layer 1 (width 2): [+] [+]\n\
+             layer 0 (width 4): [*] [*] [*] [*]\n\
+             inputs (width 8)"
+        );
+        assert_eq!(circuit.to_string(), circuit.render());
+
+        let scaled = Circuit::<Fq>::with_constants(
+            vec![vec![Operation::Xor, Operation::Mul]],
+            vec![Some(vec![Fq::from(1), Fq::from(3)])],
+        );
+        assert_eq!(
+            scaled.render(),
+            "layer 0 (width 2): [^] [3*]\ninputs (width 4)"
+        );
+    }
+
+    #[test]
+    fn it_decodes_selector_entries_into_wiring() {
+        let (circuit, _) = sample_circuit_and_inputs();
+
+        let top = circuit.layer(2);
+        assert_eq!(top.wiring(Operation::Add), vec![(0, 0, 1)]);
+        assert!(top.wiring(Operation::Mul).is_empty());
+
+        assert_eq!(
+            circuit.layer(1).wiring(Operation::Add),
+            vec![(0, 0, 1), (1, 2, 3)]
+        );
+        assert_eq!(
+            circuit.layer(0).wiring(Operation::Mul),
+            vec![(0, 0, 1), (1, 2, 3), (2, 4, 5), (3, 6, 7)]
+        );
+    }
+
+    #[test]
+    fn it_builds_the_output_poly_like_prove() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let output_poly = circuit.output_poly(&inputs);
+        let proof = prove(&mut circuit, &inputs);
+
+        assert_eq!(output_poly.evaluation, vec![Fq::from(27), Fq::from(0)]);
+        assert_eq!(&output_poly, proof.output_poly());
+    }
+
+    #[test]
+    fn it_checks_circuit_equivalence_on_random_inputs() {
+        let structure: Vec<Vec<Operation>> =
+            vec![vec![Operation::Mul, Operation::Add], vec![Operation::Add]];
+        let modified_structure: Vec<Vec<Operation>> =
+            vec![vec![Operation::Mul, Operation::Mul], vec![Operation::Add]];
+
+        let circuit: Circuit<Fq> = Circuit::new(structure);
+        let modified_circuit: Circuit<Fq> = Circuit::new(modified_structure);
+        let mut rng = test_rng();
+
+        assert!(circuit.probabilistic_equiv(&circuit.clone(), &mut rng, 10));
+        assert!(!circuit.probabilistic_equiv(&modified_circuit, &mut rng, 10));
+    }
+
+    #[test]
+    fn it_evaluates_layers_with_constants() {
+        let structure: Vec<Vec<Operation>> =
+            vec![vec![Operation::Mul, Operation::Mul], vec![Operation::Add]];
+        let constants = vec![Some(vec![Fq::from(2), Fq::from(2)]), None];
+
+        let inputs: Vec<Fq> = vec![Fq::from(1), Fq::from(2), Fq::from(3), Fq::from(4)];
+
+        let expected_evaluations: Vec<Vec<Fq>> =
+            vec![vec![Fq::from(4), Fq::from(24)], vec![Fq::from(28)]];
+
+        let mut circuit = Circuit::with_constants(structure, constants);
+
+        let evaluations = circuit.evaluate(&inputs);
+
+        assert_eq!(evaluations.layers(), expected_evaluations);
+    }
+
+    #[test]
+    fn it_composes_circuits() {
+        let (circuit, inputs) = sample_circuit_and_inputs();
+        let bottom = Circuit::<Fq>::new(vec![vec![Operation::Mul; 4]]);
+        let top = Circuit::<Fq>::new(vec![vec![Operation::Add; 2], vec![Operation::Add]]);
+
+        let composed = bottom.then(&top).unwrap();
+
+        assert_eq!(composed.depth(), 3);
+        assert_eq!(
+            composed.evaluate_layer(&inputs, 2),
+            circuit.evaluate_layer(&inputs, 2)
+        );
+        assert_eq!(
+            composed.evaluate_layer(&inputs, 2),
+            top.evaluate_layer(&bottom.evaluate_layer(&inputs, 0), 1)
+        );
+
+        assert_eq!(
+            top.then(&bottom).map(|_| ()),
+            Err(CircuitError::WidthMismatch {
+                output: 1,
+                input: 8
+            })
+        );
+    }
+
+    #[cfg(feature = "lookup")]
+    #[test]
+    fn it_shifts_lookup_tables_when_composing() {
+        let table = |entries: [u64; 2]| entries.into_iter().map(Fq::from).collect();
+
+        let mut bottom = Circuit::<Fq>::new(vec![vec![Operation::Lookup(0); 2]]);
+        bottom.register_table(table([1, 0]));
+        let mut top = Circuit::<Fq>::new(vec![vec![Operation::Lookup(0)]]);
+        top.register_table(table([3, 5]));
+
+        let composed = bottom.then(&top).unwrap();
+        let inputs = [1, 0, 0, 1].map(Fq::from);
+
+        assert_eq!(*composed.tables, vec![table([1, 0]), table([3, 5])]);
+        assert_eq!(composed.layers[1].gates[0].op, Operation::Lookup(1));
+        assert_eq!(composed.evaluate_layer(&inputs, 1), vec![Fq::from(3)]);
+    }
+
+    #[test]
+    fn it_estimates_soundness_error_from_the_circuit_dimensions() {
+        let (circuit, _) = sample_circuit_and_inputs();
+
+        // 2 + 4 + 6 sum-check rounds of degree 2, two claim folds and one
+        // output variable.
+        let bad_events = (2 + 4 + 6) * 2 + 2 + 1;
+
+        assert_eq!(
+            circuit.soundness_error(64),
+            bad_events as f64 / 2f64.powi(64)
+        );
+        assert_eq!(circuit.soundness_error(0), 27.0);
+
+        // 27 / 2^24 > 1e-6 >= 27 / 2^25
+        assert_eq!(circuit.min_field_bits(1e-6), 25);
+        assert_eq!(circuit.min_field_bits(27.0 / 2f64.powi(254)), 254);
+        assert_eq!(circuit.soundness_error(1 << 40), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be positive and finite")]
+    fn it_rejects_a_nan_target_error() {
+        let (circuit, _) = sample_circuit_and_inputs();
+
+        circuit.min_field_bits(f64::NAN);
+    }
+
+    #[test]
+    fn it_pushes_layers_of_matching_width() {
+        let (mut expected, inputs) = sample_circuit_and_inputs();
+
+        let mut circuit = Circuit::<Fq>::new(Vec::new());
+        assert_eq!(circuit.push_layer(vec![Operation::Mul; 4]), Ok(()));
+        assert_eq!(circuit.push_layer(vec![Operation::Add; 2]), Ok(()));
+
+        assert_eq!(
+            circuit.push_layer(vec![Operation::Add; 2]),
+            Err(CircuitError::WidthMismatch {
+                output: 2,
+                input: 4
+            })
+        );
+        assert_eq!(
+            circuit.push_layer(Vec::new()),
+            Err(CircuitError::WidthMismatch {
+                output: 2,
+                input: 0
+            })
+        );
+        assert_eq!(circuit.depth(), 2);
+
+        assert_eq!(circuit.push_layer(vec![Operation::Add]), Ok(()));
+        assert_eq!(circuit.evaluate(&inputs), expected.evaluate(&inputs));
+    }
+
+    #[test]
+    fn it_cannot_stack_a_circuit_on_itself() {
+        let (circuit, _) = sample_circuit_and_inputs();
+
+        assert_eq!(
+            circuit.then(&circuit).map(|_| ()),
+            Err(CircuitError::WidthMismatch {
+                output: 1,
+                input: 8
+            })
+        );
+    }
+
+    #[test]
+    fn it_reports_inputs_no_output_depends_on() {
+        let structure: Vec<Vec<Operation>> =
+            vec![vec![Operation::Mul, Operation::Add], vec![Operation::Add]];
+        let constants = vec![Some(vec![Fq::from(1), Fq::from(0)]), None];
+
+        let circuit = Circuit::<Fq>::with_constants(structure, constants);
+        let unused = circuit.unused_inputs();
+
+        assert!(unused.contains(&3));
+        assert_eq!(unused, vec![2, 3]);
+
+        let full = Circuit::<Fq>::new(vec![
+            vec![Operation::Mul, Operation::Add],
+            vec![Operation::Add],
+        ]);
+        assert!(full.unused_inputs().is_empty());
+
+        let mut square = Circuit::<Fq>::new(vec![
+            vec![Operation::Custom(0), Operation::Add],
+            vec![Operation::Add],
+        ]);
+        square.register_gate(Arc::new(SquarePlus { scale: 0 }));
+        assert_eq!(square.layer(0).reads_inputs(0), (true, false));
+        assert_eq!(square.unused_inputs(), vec![1]);
+
+        #[cfg(feature = "lookup")]
+        {
+            let mut lookup = Circuit::<Fq>::new(vec![
+                vec![Operation::Lookup(0), Operation::Lookup(1)],
+                vec![Operation::Add],
+            ]);
+            lookup.register_table(vec![Fq::from(3), Fq::from(5)]);
+            lookup.register_table(vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(1)]);
+            assert_eq!(lookup.layer(0).reads_inputs(0), (true, false));
+            assert_eq!(lookup.layer(0).reads_inputs(1), (true, true));
+            assert_eq!(lookup.unused_inputs(), vec![1]);
+        }
+    }
+
+    #[test]
+    fn it_splits_xor_gates_across_the_fbc_selectors() {
+        for (a, b, expected) in [(0, 0, 0), (0, 1, 1), (1, 0, 1), (1, 1, 0)] {
+            assert_eq!(
+                Operation::Xor.apply(Fq::from(a), Fq::from(b)),
+                Fq::from(expected)
+            );
+        }
+        assert_eq!(
+            Operation::Custom(0).try_apply(Fq::from(1), Fq::from(2)),
+            None
+        );
+
+        let layer = Layer::with_constants(
+            vec![
+                Gate::new(Fq::from(1), Fq::from(1), Operation::Xor),
+                Gate::new(Fq::from(1), Fq::from(0), Operation::Add),
+            ],
+            vec![Fq::from(3), Fq::from(1)],
+        );
+
+        let (sum_selector, product_selector) = layer.get_fbc_selectors();
+        let xor_i = layer.get_add_mul_i(Operation::Xor);
+
+        assert_eq!(
+            sum_selector,
+            layer.get_add_mul_i(Operation::Add) + xor_i.clone()
+        );
+        assert_eq!(
+            product_selector,
+            layer.get_add_mul_i(Operation::Mul) + xor_i.scale(-Fq::from(2))
+        );
+        assert_eq!(layer.wiring(Operation::Xor), vec![(0, 0, 1)]);
+    }
+
+    #[test]
+    fn it_puts_gate_constants_in_the_selector() {
+        let gate = Gate::new(Fq::from(1), Fq::from(2), Operation::Add);
+
+        let layer = Layer::with_constants(vec![gate], vec![Fq::from(3)]);
+
+        let add_poly = layer.get_add_mul_i(Operation::Add);
+
+        assert_eq!(add_poly.evaluation[1], Fq::from(3));
+        assert_eq!(layer.get_layer_poly(), vec![Fq::from(9)]);
+    }
+
+    #[test]
+    fn it_counts_sumcheck_rounds_per_layer() {
+        let structure: Vec<Vec<Operation>> = vec![
+            vec![
+                Operation::Mul,
+                Operation::Mul,
+                Operation::Mul,
+                Operation::Mul,
+            ],
+            vec![Operation::Add, Operation::Add],
+            vec![Operation::Add],
+        ];
+
+        let circuit: Circuit<Fq> = Circuit::new(structure);
+
+        let rounds: Vec<usize> = circuit
+            .layers
+            .iter()
+            .map(|layer| layer.num_sumcheck_rounds())
+            .collect();
+
+        assert_eq!(rounds, vec![6, 4, 2]);
+    }
+
+    #[test]
+    fn it_keeps_a_selector_entry_per_gate_for_identical_gates() {
+        let gate_1 = Gate::new(Fq::from(1), Fq::from(2), Operation::Add);
+        let gate_2 = Gate::new(Fq::from(1), Fq::from(2), Operation::Add);
+
+        let layer = Layer::new(vec![gate_1, gate_2]);
+
+        let gate_positions = layer.gate_to_bits();
+        assert_ne!(gate_positions[0], gate_positions[1]);
+
+        let add_poly = layer.get_add_mul_i(Operation::Add);
+        let set_entries = add_poly
+            .evaluation
+            .iter()
+            .filter(|eval| **eval == Fq::from(1))
+            .count();
 
-        assert_eq!(evaluations, expected_evaluations);
+        assert_eq!(set_entries, 2);
     }
 
     #[test]
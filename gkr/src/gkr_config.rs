@@ -0,0 +1,133 @@
+use ark_ff::PrimeField;
+
+/// How inputs shorter than the circuit's input width are lifted into the
+/// input layer's multilinear polynomial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaddingPolicy {
+    #[default]
+    ZeroPad,
+    Error,
+    RepeatLast,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaddingError {
+    pub expected: usize,
+    pub actual: usize,
+}
+
+/// How a layer's fbc poly is laid out for sum-check: the add and mul terms as
+/// two products, or as one product over an extra variable choosing between
+/// them, at the cost of one more round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FbcConstruction {
+    #[default]
+    SumOfProducts,
+    SingleProduct,
+}
+
+impl FbcConstruction {
+    pub fn extra_rounds(&self) -> usize {
+        match self {
+            FbcConstruction::SumOfProducts => 0,
+            FbcConstruction::SingleProduct => 1,
+        }
+    }
+}
+
+/// How the verifier evaluates each layer's add/mul selectors for the final
+/// sum-check claim: from the full selector tables, or gate by gate from the
+/// wiring without allocating them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectorEvaluation {
+    #[default]
+    Table,
+    Direct,
+}
+
+/// Whether `verify_with_mode` stops at the first failing layer or checks
+/// every layer and reports each outcome.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerifyMode {
+    #[default]
+    FailFast,
+    CheckAll,
+}
+
+/// Largest selector polynomial, in evaluations, a layer may allocate.
+pub const DEFAULT_MAX_SELECTOR_SIZE: usize = 1 << 24;
+
+#[derive(Debug, Clone)]
+pub struct GkrConfig {
+    pub padding: PaddingPolicy,
+    pub max_selector_size: usize,
+    pub fbc_construction: FbcConstruction,
+    pub selector_evaluation: SelectorEvaluation,
+}
+
+impl Default for GkrConfig {
+    fn default() -> Self {
+        Self {
+            padding: PaddingPolicy::default(),
+            max_selector_size: DEFAULT_MAX_SELECTOR_SIZE,
+            fbc_construction: FbcConstruction::default(),
+            selector_evaluation: SelectorEvaluation::default(),
+        }
+    }
+}
+
+impl PaddingPolicy {
+    pub fn apply<F: PrimeField>(&self, inputs: &[F], width: usize) -> Result<Vec<F>, PaddingError> {
+        let error = PaddingError {
+            expected: width,
+            actual: inputs.len(),
+        };
+
+        if inputs.len() > width {
+            return Err(error);
+        }
+
+        let fill = match self {
+            _ if inputs.len() == width => return Ok(inputs.to_vec()),
+            PaddingPolicy::ZeroPad => F::zero(),
+            PaddingPolicy::RepeatLast => *inputs.last().ok_or(error)?,
+            PaddingPolicy::Error => return Err(error),
+        };
+
+        let mut padded = inputs.to_vec();
+        padded.resize(width, fill);
+
+        Ok(padded)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn it_pads_inputs_per_policy() {
+        let inputs: Vec<Fq> = (1..=6).map(Fq::from).collect();
+
+        let zero_padded = PaddingPolicy::ZeroPad.apply(&inputs, 8).unwrap();
+        assert_eq!(&zero_padded[..6], &inputs[..]);
+        assert_eq!(&zero_padded[6..], &[Fq::from(0), Fq::from(0)]);
+
+        let repeated = PaddingPolicy::RepeatLast.apply(&inputs, 8).unwrap();
+        assert_eq!(&repeated[..6], &inputs[..]);
+        assert_eq!(&repeated[6..], &[Fq::from(6), Fq::from(6)]);
+
+        assert_eq!(
+            PaddingPolicy::Error.apply(&inputs, 8),
+            Err(PaddingError {
+                expected: 8,
+                actual: 6
+            })
+        );
+
+        assert_eq!(PaddingPolicy::Error.apply(&inputs, 6).unwrap(), inputs);
+        assert!(PaddingPolicy::ZeroPad.apply(&inputs, 4).is_err());
+        assert!(PaddingPolicy::RepeatLast.apply::<Fq>(&[], 4).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::gkr_circuit::{Circuit, Operation};
+
+use ark_ff::PrimeField;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wire {
+    Input(usize),
+    Gate(usize),
+}
+
+/// One gate of a flat gate list, reading a circuit input or another gate's
+/// output on each side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GateSpec {
+    pub op: Operation,
+    pub left: Wire,
+    pub right: Wire,
+}
+
+/// Gate indices refer to positions in the list passed to `from_gate_dag`.
+/// Depth 0 is the circuit inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayeringError {
+    Empty,
+    UnknownGate { gate: usize },
+    Cycle { gate: usize },
+    SkipsLayer { gate: usize },
+    MisalignedInputs { gate: usize },
+    WireReused { gate: usize },
+    UnconsumedWire { depth: usize, wire: usize },
+}
+
+impl<F: PrimeField> Circuit<F> {
+    /// Layers a gate DAG by dependency depth and orders each layer so the
+    /// result uses positional wiring. Both inputs of a gate must come from the
+    /// layer directly below, as the wire pair `2i, 2i + 1` for some `i`, and
+    /// every wire below the output layer must be read exactly once.
+    pub fn from_gate_dag(gates: &[GateSpec]) -> Result<Self, LayeringError> {
+        if gates.is_empty() {
+            return Err(LayeringError::Empty);
+        }
+
+        let depths = gate_depths(gates)?;
+        let num_layers = depths.iter().copied().max().unwrap_or(0);
+
+        let mut positions = vec![0; gates.len()];
+        let mut structure: Vec<Vec<Operation>> = Vec::with_capacity(num_layers);
+
+        for depth in 1..=num_layers {
+            let mut layer: Vec<Option<Operation>> = Vec::new();
+
+            for (gate, spec) in gates.iter().enumerate() {
+                if depths[gate] != depth {
+                    continue;
+                }
+
+                let position_of = |wire| match wire {
+                    Wire::Input(index) => index,
+                    Wire::Gate(index) => positions[index],
+                };
+                let (left, right) = (position_of(spec.left), position_of(spec.right));
+
+                if left & 1 == 1 || right != left + 1 {
+                    return Err(LayeringError::MisalignedInputs { gate });
+                }
+
+                let position = left / 2;
+                if layer.len() <= position {
+                    layer.resize(position + 1, None);
+                }
+
+                if layer[position].is_some() {
+                    return Err(LayeringError::WireReused { gate });
+                }
+
+                layer[position] = Some(spec.op);
+                positions[gate] = position;
+            }
+
+            let width = match structure.last() {
+                Some(below) => below.len().div_ceil(2),
+                None => layer.len(),
+            };
+            layer.resize(width, None);
+
+            let mut ops = Vec::with_capacity(width);
+            for (position, op) in layer.into_iter().enumerate() {
+                let op = op.ok_or(LayeringError::UnconsumedWire {
+                    depth: depth - 1,
+                    wire: 2 * position,
+                })?;
+                ops.push(op);
+            }
+
+            structure.push(ops);
+        }
+
+        Ok(Circuit::new(structure))
+    }
+}
+
+/// Each gate's depth, one more than that of its inputs, which must match.
+fn gate_depths(gates: &[GateSpec]) -> Result<Vec<usize>, LayeringError> {
+    let mut depths = vec![None; gates.len()];
+    let mut visiting = vec![false; gates.len()];
+
+    for gate in 0..gates.len() {
+        gate_depth(gates, gate, &mut depths, &mut visiting)?;
+    }
+
+    Ok(depths.into_iter().flatten().collect())
+}
+
+fn gate_depth(
+    gates: &[GateSpec],
+    gate: usize,
+    depths: &mut [Option<usize>],
+    visiting: &mut [bool],
+) -> Result<usize, LayeringError> {
+    if let Some(depth) = depths[gate] {
+        return Ok(depth);
+    }
+
+    if visiting[gate] {
+        return Err(LayeringError::Cycle { gate });
+    }
+    visiting[gate] = true;
+
+    let mut input_depths = [0; 2];
+    for (slot, wire) in input_depths
+        .iter_mut()
+        .zip([gates[gate].left, gates[gate].right])
+    {
+        *slot = match wire {
+            Wire::Input(_) => 0,
+            Wire::Gate(index) if index < gates.len() => gate_depth(gates, index, depths, visiting)?,
+            Wire::Gate(_) => return Err(LayeringError::UnknownGate { gate }),
+        };
+    }
+
+    if input_depths[0] != input_depths[1] {
+        return Err(LayeringError::SkipsLayer { gate });
+    }
+
+    visiting[gate] = false;
+    depths[gate] = Some(input_depths[0] + 1);
+
+    Ok(input_depths[0] + 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GateSpec, LayeringError, Wire};
+    use crate::{
+        gkr_circuit::{Circuit, Operation},
+        gkr_protocol::{prove, verify},
+        test_util::sample_circuit_and_inputs,
+    };
+    use ark_bn254::Fq;
+
+    fn spec(op: Operation, left: Wire, right: Wire) -> GateSpec {
+        GateSpec { op, left, right }
+    }
+
+    #[test]
+    fn it_layers_a_gate_dag() {
+        use Operation::{Add, Mul};
+        use Wire::{Gate, Input};
+
+        // The sample circuit, listed out of order.
+        let gates = [
+            spec(Add, Gate(5), Gate(6)),
+            spec(Mul, Input(6), Input(7)),
+            spec(Mul, Input(0), Input(1)),
+            spec(Mul, Input(4), Input(5)),
+            spec(Mul, Input(2), Input(3)),
+            spec(Add, Gate(2), Gate(4)),
+            spec(Add, Gate(3), Gate(1)),
+        ];
+
+        let mut circuit: Circuit<Fq> = Circuit::from_gate_dag(&gates).unwrap();
+        let (mut expected, inputs) = sample_circuit_and_inputs();
+
+        let ops = |circuit: &Circuit<Fq>| -> Vec<Vec<Operation>> {
+            circuit
+                .layers
+                .iter()
+                .map(|layer| layer.gates.iter().map(|gate| gate.op).collect())
+                .collect()
+        };
+        assert_eq!(ops(&circuit), ops(&expected));
+        assert_eq!(circuit.evaluate(&inputs), expected.evaluate(&inputs));
+
+        let proof = prove(&mut circuit, &inputs);
+        assert_eq!(proof.output_poly().evaluation[0], Fq::from(27));
+        assert!(verify(proof, circuit, &inputs));
+    }
+
+    #[test]
+    fn it_rejects_dags_it_cannot_layer() {
+        use Operation::{Add, Mul};
+        use Wire::{Gate, Input};
+
+        let layer = |gates: &[GateSpec]| Circuit::<Fq>::from_gate_dag(gates).map(|_| ());
+
+        assert_eq!(layer(&[]), Err(LayeringError::Empty));
+        assert_eq!(
+            layer(&[spec(Add, Gate(1), Input(0)), spec(Mul, Gate(0), Input(1))]),
+            Err(LayeringError::Cycle { gate: 0 })
+        );
+        assert_eq!(
+            layer(&[spec(Add, Gate(3), Input(1))]),
+            Err(LayeringError::UnknownGate { gate: 0 })
+        );
+        assert_eq!(
+            layer(&[spec(Mul, Input(0), Input(1)), spec(Add, Gate(0), Input(2))]),
+            Err(LayeringError::SkipsLayer { gate: 1 })
+        );
+        assert_eq!(
+            layer(&[spec(Mul, Input(1), Input(2))]),
+            Err(LayeringError::MisalignedInputs { gate: 0 })
+        );
+        assert_eq!(
+            layer(&[spec(Mul, Input(0), Input(1)), spec(Add, Input(0), Input(1))]),
+            Err(LayeringError::WireReused { gate: 1 })
+        );
+        assert_eq!(
+            layer(&[spec(Mul, Input(2), Input(3))]),
+            Err(LayeringError::UnconsumedWire { depth: 0, wire: 0 })
+        );
+        assert_eq!(
+            layer(&[
+                spec(Mul, Input(0), Input(1)),
+                spec(Mul, Input(2), Input(3)),
+                spec(Mul, Input(4), Input(5)),
+                spec(Add, Gate(0), Gate(1)),
+            ]),
+            Err(LayeringError::UnconsumedWire { depth: 1, wire: 2 })
+        );
+    }
+}
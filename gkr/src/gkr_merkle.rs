@@ -0,0 +1,164 @@
+use crate::{
+    gkr_circuit::Circuit,
+    gkr_protocol::{verify, Proof},
+};
+
+use ark_ff::PrimeField;
+use fiat_shamir::fiat_shamir_transcript::field_vec_to_bytes;
+use sha3::{Digest, Keccak256};
+
+pub type MerkleHash = [u8; 32];
+
+/// Sibling hashes from a leaf up to the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerklePath {
+    pub siblings: Vec<MerkleHash>,
+}
+
+impl MerklePath {
+    pub fn compute_root<F: PrimeField>(&self, index: usize, leaf: F) -> MerkleHash {
+        let mut hash = hash_leaf(leaf);
+        let mut index = index;
+
+        for sibling in &self.siblings {
+            hash = if index & 1 == 0 {
+                hash_pair(&hash, sibling)
+            } else {
+                hash_pair(sibling, &hash)
+            };
+            index /= 2;
+        }
+
+        hash
+    }
+}
+
+pub fn hash_leaf<F: PrimeField>(leaf: F) -> MerkleHash {
+    Keccak256::digest(field_vec_to_bytes(&[leaf])).into()
+}
+
+pub fn hash_pair(left: &MerkleHash, right: &MerkleHash) -> MerkleHash {
+    let mut hasher = Keccak256::new();
+    hasher.update(left);
+    hasher.update(right);
+
+    hasher.finalize().into()
+}
+
+/// Verifies `proof` against inputs given as members of the Merkle tree with
+/// `root`. Every input index must be opened exactly once.
+pub fn verify_with_merkle_inputs<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F>,
+    root: MerkleHash,
+    members: &[(usize, F, MerklePath)],
+) -> bool {
+    let mut inputs: Vec<Option<F>> = vec![None; circuit.input_width()];
+
+    for (index, value, path) in members {
+        if path.compute_root(*index, *value) != root {
+            return false;
+        }
+
+        match inputs.get_mut(*index) {
+            Some(slot @ None) => *slot = Some(*value),
+            _ => return false,
+        }
+    }
+
+    let inputs: Option<Vec<F>> = inputs.into_iter().collect();
+
+    match inputs {
+        Some(inputs) => verify(proof, circuit, &inputs),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{gkr_circuit::Operation, gkr_protocol::prove};
+    use ark_bn254::Fq;
+
+    struct MockMerkleTree {
+        levels: Vec<Vec<MerkleHash>>,
+    }
+
+    impl MockMerkleTree {
+        fn new(leaves: &[Fq]) -> Self {
+            let mut levels = vec![leaves
+                .iter()
+                .map(|leaf| hash_leaf(*leaf))
+                .collect::<Vec<_>>()];
+
+            while levels.last().unwrap().len() > 1 {
+                let next = levels
+                    .last()
+                    .unwrap()
+                    .chunks_exact(2)
+                    .map(|pair| hash_pair(&pair[0], &pair[1]))
+                    .collect();
+                levels.push(next);
+            }
+
+            Self { levels }
+        }
+
+        fn root(&self) -> MerkleHash {
+            self.levels.last().unwrap()[0]
+        }
+
+        fn path(&self, index: usize) -> MerklePath {
+            let siblings = self.levels[..self.levels.len() - 1]
+                .iter()
+                .enumerate()
+                .map(|(depth, level)| level[(index >> depth) ^ 1])
+                .collect();
+
+            MerklePath { siblings }
+        }
+    }
+
+    #[test]
+    fn test_verify_with_merkle_inputs() {
+        let circuit_structure: Vec<Vec<Operation>> =
+            vec![vec![Operation::Mul, Operation::Mul], vec![Operation::Add]];
+        let inputs: Vec<Fq> = vec![Fq::from(1), Fq::from(2), Fq::from(3), Fq::from(4)];
+
+        let tree = MockMerkleTree::new(&inputs);
+        let members: Vec<(usize, Fq, MerklePath)> = inputs
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (index, *value, tree.path(index)))
+            .collect();
+
+        let mut circuit = Circuit::new(circuit_structure);
+
+        let proof = prove(&mut circuit, &inputs);
+        assert!(verify_with_merkle_inputs(
+            proof,
+            circuit.clone(),
+            tree.root(),
+            &members
+        ));
+
+        let mut tampered = members.clone();
+        tampered[2].1 = Fq::from(5);
+
+        let proof = prove(&mut circuit, &inputs);
+        assert!(!verify_with_merkle_inputs(
+            proof,
+            circuit.clone(),
+            tree.root(),
+            &tampered
+        ));
+
+        let proof = prove(&mut circuit, &inputs);
+        assert!(!verify_with_merkle_inputs(
+            proof,
+            circuit,
+            tree.root(),
+            &members[..3]
+        ));
+    }
+}
@@ -0,0 +1,473 @@
+use crate::{
+    gkr_challenges::ChallengeStream,
+    gkr_circuit::{Circuit, LayerSource, FBC_ROUND_DEGREE},
+    gkr_config::FbcConstruction,
+    gkr_protocol::{
+        combine_verifier_claim, evaluate_input_poly, needs_sum_of_products, prove, GkrError, Proof,
+        ProofShape,
+    },
+};
+
+use ark_ff::PrimeField;
+use fiat_shamir::fiat_shamir_transcript::Transcript;
+use multilinear_polynomial::multilinear_polynomial_evaluation::MultilinearPoly;
+use std::fmt;
+use sum_check::sum_check_protocol::gkr_verify;
+
+/// A commitment scheme for selector polys: the verifier keeps only
+/// commitments and checks the prover's openings at the points it needs.
+pub trait SelectorCommitment<F: PrimeField> {
+    type Commitment: Clone + fmt::Debug + PartialEq;
+    type Opening: Clone + fmt::Debug;
+
+    fn commit(&self, selector: &MultilinearPoly<F>) -> Self::Commitment;
+
+    fn open(&self, selector: &MultilinearPoly<F>, point: &[F]) -> Self::Opening;
+
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        point: &[F],
+        value: F,
+        opening: &Self::Opening,
+    ) -> bool;
+}
+
+/// What the verifier keeps of one layer: its number of sum-check rounds and
+/// the commitments to its `add_i` and `mul_i` selectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerKey<C> {
+    pub num_rounds: usize,
+    pub add_i: C,
+    pub mul_i: C,
+}
+
+/// Everything `verify_preprocessed` needs of a circuit, computed once by
+/// `Circuit::preprocess`. Layers are top-down, in protocol order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifierKey<C> {
+    pub input_width: usize,
+    pub output_vars: usize,
+    pub layers: Vec<LayerKey<C>>,
+}
+
+/// A selector's claimed value at a point and the opening proving it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectorOpening<F, O> {
+    pub value: F,
+    pub opening: O,
+}
+
+/// Both selectors of a layer opened at the same point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointOpenings<F, O> {
+    pub add_i: SelectorOpening<F, O>,
+    pub mul_i: SelectorOpening<F, O>,
+}
+
+/// A proof with every layer's selectors opened at the points its final
+/// claim reads them at: the output challenge for the output layer, and `r_b`
+/// and `r_c` of the layer above for every other layer, each followed by the
+/// layer's own sum-check challenges.
+#[derive(Debug, PartialEq)]
+pub struct PreprocessedProof<F: PrimeField, O> {
+    pub proof: Proof<F>,
+    pub openings: Vec<Vec<PointOpenings<F, O>>>,
+}
+
+impl<F: PrimeField, S: LayerSource<F>> Circuit<F, S> {
+    /// Commits to every layer's `add_i` and `mul_i` selectors. Lookup and
+    /// custom gates have selectors of their own and skip connections make
+    /// extra claims, none of which a verifier key holds, so circuits using
+    /// them are rejected with `GkrError::UnsupportedConstruction`.
+    pub fn preprocess<C: SelectorCommitment<F>>(
+        &self,
+        scheme: &C,
+    ) -> Result<VerifierKey<C::Commitment>, GkrError> {
+        let layers = self
+            .layers_top_down()
+            .enumerate()
+            .map(|(i, layer)| {
+                if needs_sum_of_products(&layer) || layer.has_skip_connections() {
+                    return Err(GkrError::UnsupportedConstruction { layer: i });
+                }
+
+                let (add_i, mul_i) = layer.get_fbc_selectors();
+
+                Ok(LayerKey {
+                    num_rounds: layer.num_sumcheck_rounds(),
+                    add_i: scheme.commit(&add_i),
+                    mul_i: scheme.commit(&mul_i),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(VerifierKey {
+            input_width: self.input_width(),
+            output_vars: ProofShape::of(self, FbcConstruction::default()).output_vars,
+            layers,
+        })
+    }
+}
+
+/// Proves like `prove` and opens every layer's selectors where
+/// `verify_preprocessed` needs them.
+pub fn prove_preprocessed<F: PrimeField, C: SelectorCommitment<F>>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+    scheme: &C,
+) -> PreprocessedProof<F, C::Opening> {
+    let proof = prove(circuit, inputs);
+    let (_, output_challenge) = proof.output_claim();
+    let challenges = proof
+        .derive_challenges(circuit)
+        .expect("a proof fresh from `prove` has the circuit's shape");
+
+    let openings = circuit
+        .layers_top_down()
+        .enumerate()
+        .map(|(i, layer)| {
+            let (add_i, mul_i) = layer.get_fbc_selectors();
+            let above = i.checked_sub(1).map(|i| challenges[i].sum_check.as_slice());
+
+            output_points(output_challenge, above)
+                .into_iter()
+                .map(|r_a| {
+                    let point = [r_a, challenges[i].sum_check.clone()].concat();
+                    let open = |selector: &MultilinearPoly<F>| SelectorOpening {
+                        value: selector.evaluate(point.clone()),
+                        opening: scheme.open(selector, &point),
+                    };
+
+                    PointOpenings {
+                        add_i: open(&add_i),
+                        mul_i: open(&mul_i),
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    PreprocessedProof { proof, openings }
+}
+
+/// Verifies `preprocessed` against only the verifier key: each layer's
+/// selector evaluations come from the proof's openings, checked against the
+/// key's commitments, instead of from the circuit.
+pub fn verify_preprocessed<F: PrimeField, C: SelectorCommitment<F>>(
+    preprocessed: &PreprocessedProof<F, C::Opening>,
+    vk: &VerifierKey<C::Commitment>,
+    inputs: &[F],
+    scheme: &C,
+) -> Result<(), GkrError> {
+    let proof = &preprocessed.proof;
+    let num_layers = vk.layers.len();
+
+    if inputs.len() != vk.input_width {
+        return Err(GkrError::InputLengthMismatch {
+            expected: vk.input_width,
+            actual: inputs.len(),
+        });
+    }
+
+    let shape = ProofShape {
+        output_vars: vk.output_vars,
+        layers: vk
+            .layers
+            .iter()
+            .map(|layer| (layer.num_rounds, FBC_ROUND_DEGREE + 1))
+            .collect(),
+        skip_layers: Vec::new(),
+    };
+
+    shape.check(proof)?;
+
+    if preprocessed.openings.len() != num_layers {
+        return Err(GkrError::MalformedProof {
+            layer: preprocessed.openings.len().min(num_layers),
+        });
+    }
+
+    shape.check_canonical(proof)?;
+
+    let mut transcript = Transcript::<F>::new();
+    let mut stream = ChallengeStream::new(&mut transcript);
+    let (mut current_claim, output_challenge) = stream.output_claim(&proof.output_poly);
+
+    let mut weights = vec![F::one()];
+    let mut above: Option<Vec<F>> = None;
+
+    for (i, layer) in vk.layers.iter().enumerate() {
+        check_round_split(vk, i)?;
+        stream.start_layer(i);
+
+        let openings = &preprocessed.openings[i];
+        let points = output_points(output_challenge, above.as_deref());
+
+        if openings.len() != points.len() {
+            return Err(GkrError::MalformedProof { layer: i });
+        }
+
+        let (a_r, m_r) = weights.iter().zip(openings).fold(
+            (F::zero(), F::zero()),
+            |(a_r, m_r), (weight, opening)| {
+                (
+                    a_r + *weight * opening.add_i.value,
+                    m_r + *weight * opening.mul_i.value,
+                )
+            },
+        );
+
+        let sum_check_verify = gkr_verify(
+            proof.proof_polynomials[i].clone(),
+            current_claim,
+            FBC_ROUND_DEGREE,
+            &mut stream,
+            |challenges| {
+                let (o_1, o_2) = if i == num_layers - 1 {
+                    evaluate_input_poly(inputs, challenges)
+                } else {
+                    proof.claimed_evaluations[i]
+                };
+
+                combine_verifier_claim(None, a_r, m_r, o_1, o_2)
+            },
+        );
+
+        let challenges = match sum_check_verify.oracle_evaluation {
+            Some(_) if sum_check_verify.random_challenges.len() == layer.num_rounds => {
+                sum_check_verify.random_challenges
+            }
+            _ => return Err(GkrError::SumCheckFailed { layer: i }),
+        };
+
+        for (r_a, opening) in points.into_iter().zip(openings) {
+            let point = [r_a, challenges.clone()].concat();
+            let opens = |commitment, selector: &SelectorOpening<F, C::Opening>| {
+                scheme.verify(commitment, &point, selector.value, &selector.opening)
+            };
+
+            if !opens(&layer.add_i, &opening.add_i) || !opens(&layer.mul_i, &opening.mul_i) {
+                return Err(GkrError::SelectorOpeningFailed { layer: i });
+            }
+        }
+
+        if !sum_check_verify.verified {
+            return Err(GkrError::ClaimMismatch { layer: i });
+        }
+
+        if i < num_layers - 1 {
+            let (o_1, o_2) = proof.claimed_evaluations[i];
+            let (alpha, beta) = stream.reduce(o_1, o_2);
+
+            current_claim = (alpha * o_1) + (beta * o_2);
+            weights = vec![alpha, beta];
+            above = Some(challenges);
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the layer at top-down index `i` of `vk` has an even number of
+/// rounds, and that the bottom layer's split into two points on the inputs.
+fn check_round_split<C>(vk: &VerifierKey<C>, i: usize) -> Result<(), GkrError> {
+    let actual = vk.layers[i].num_rounds;
+    let expected = match i == vk.layers.len() - 1 {
+        true => 2 * vk.input_width.max(1).ilog2() as usize,
+        false => actual.next_multiple_of(2),
+    };
+
+    match actual == expected {
+        true => Ok(()),
+        false => Err(GkrError::ChallengeSplitMismatch {
+            layer: i,
+            expected,
+            actual,
+        }),
+    }
+}
+
+/// The output-gate points a layer's selectors are read at: the output
+/// challenge for the output layer, otherwise `r_b` and `r_c` from the
+/// sum-check challenges of the layer above.
+fn output_points<F: PrimeField>(output_challenge: F, above: Option<&[F]>) -> Vec<Vec<F>> {
+    match above {
+        None => vec![vec![output_challenge]],
+        Some(challenges) => {
+            let (r_b, r_c) = challenges.split_at(challenges.len() / 2);
+            debug_assert!(
+                r_b.len() == r_c.len(),
+                "{} challenges do not split into r_b and r_c",
+                challenges.len()
+            );
+
+            vec![r_b.to_vec(), r_c.to_vec()]
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        gkr_circuit::Operation,
+        test_util::{sample_circuit_and_inputs, SquarePlus},
+    };
+    use ark_bn254::Fq;
+    use fiat_shamir::fiat_shamir_transcript::field_vec_to_bytes;
+    use sha3::{Digest, Keccak256};
+    use std::sync::Arc;
+
+    /// Commits to a selector's hashed table and opens it by revealing the
+    /// whole table: binding, but neither hiding nor succinct.
+    struct MockCommitment;
+
+    impl SelectorCommitment<Fq> for MockCommitment {
+        type Commitment = [u8; 32];
+        type Opening = Vec<Fq>;
+
+        fn commit(&self, selector: &MultilinearPoly<Fq>) -> [u8; 32] {
+            Keccak256::digest(field_vec_to_bytes(&selector.evaluation)).into()
+        }
+
+        fn open(&self, selector: &MultilinearPoly<Fq>, _point: &[Fq]) -> Vec<Fq> {
+            selector.evaluation.clone()
+        }
+
+        fn verify(
+            &self,
+            commitment: &[u8; 32],
+            point: &[Fq],
+            value: Fq,
+            opening: &Vec<Fq>,
+        ) -> bool {
+            let selector = MultilinearPoly::new(opening.clone());
+
+            self.commit(&selector) == *commitment && selector.evaluate(point.to_vec()) == value
+        }
+    }
+
+    #[test]
+    fn test_verify_preprocessed() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let vk = circuit.preprocess(&MockCommitment).unwrap();
+
+        assert_eq!(vk.input_width, 8);
+        assert_eq!(vk.layers.len(), 3);
+
+        let preprocessed = prove_preprocessed(&mut circuit, &inputs, &MockCommitment);
+        assert_eq!(preprocessed.openings[0].len(), 1);
+        assert_eq!(preprocessed.openings[2].len(), 2);
+        assert_eq!(
+            verify_preprocessed(&preprocessed, &vk, &inputs, &MockCommitment),
+            Ok(())
+        );
+
+        let other = Circuit::<Fq>::new(vec![
+            vec![Operation::Mul; 4],
+            vec![Operation::Add, Operation::Mul],
+            vec![Operation::Add],
+        ]);
+        let other_vk = other.preprocess(&MockCommitment).unwrap();
+        assert_eq!(
+            verify_preprocessed(&preprocessed, &other_vk, &inputs, &MockCommitment),
+            Err(GkrError::SelectorOpeningFailed { layer: 1 })
+        );
+
+        let mut tampered = prove_preprocessed(&mut circuit, &inputs, &MockCommitment);
+        tampered.openings[1][0].mul_i.value += Fq::from(1);
+        assert_eq!(
+            verify_preprocessed(&tampered, &vk, &inputs, &MockCommitment),
+            Err(GkrError::SelectorOpeningFailed { layer: 1 })
+        );
+
+        let mut other_inputs = inputs.clone();
+        other_inputs[0] += Fq::from(1);
+        assert_eq!(
+            verify_preprocessed(&preprocessed, &vk, &other_inputs, &MockCommitment),
+            Err(GkrError::ClaimMismatch { layer: 2 })
+        );
+    }
+
+    #[test]
+    fn test_preprocess_rejects_unsupported_layers() {
+        let (mut circuit, _) = sample_circuit_and_inputs();
+        circuit.add_skip_connection(2, 0).unwrap();
+        assert_eq!(
+            circuit.preprocess(&MockCommitment),
+            Err(GkrError::UnsupportedConstruction { layer: 0 })
+        );
+
+        let mut custom = Circuit::<Fq>::new(vec![
+            vec![Operation::Add; 4],
+            vec![Operation::Custom(0), Operation::Mul],
+            vec![Operation::Add],
+        ]);
+        custom.register_gate(Arc::new(SquarePlus { scale: 1 }));
+        assert_eq!(
+            custom.preprocess(&MockCommitment),
+            Err(GkrError::UnsupportedConstruction { layer: 1 })
+        );
+    }
+
+    #[test]
+    fn test_verify_preprocessed_rejects_malformed_proofs() {
+        use ark_ff::{BigInteger, Fp, PrimeField};
+
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let vk = circuit.preprocess(&MockCommitment).unwrap();
+
+        let mut wide_output = prove_preprocessed(&mut circuit, &inputs, &MockCommitment);
+        wide_output.proof.output_poly = MultilinearPoly::new(vec![Fq::from(1); 4]);
+        assert_eq!(
+            verify_preprocessed(&wide_output, &vk, &inputs, &MockCommitment),
+            Err(GkrError::MalformedProof { layer: 0 })
+        );
+
+        let mut non_canonical = prove_preprocessed(&mut circuit, &inputs, &MockCommitment);
+        let mut repr = non_canonical.proof.claimed_evaluations[1].0 .0;
+        repr.add_with_carry(&Fq::MODULUS);
+        non_canonical.proof.claimed_evaluations[1].0 = Fp::new_unchecked(repr);
+        assert_eq!(
+            verify_preprocessed(&non_canonical, &vk, &inputs, &MockCommitment),
+            Err(GkrError::NonCanonicalElement { layer: 1 })
+        );
+
+        let mut odd_vk = vk.clone();
+        odd_vk.layers[1].num_rounds = 3;
+        let mut odd_rounds = prove_preprocessed(&mut circuit, &inputs, &MockCommitment);
+        odd_rounds.proof.proof_polynomials[1].pop();
+        assert_eq!(
+            verify_preprocessed(&odd_rounds, &odd_vk, &inputs, &MockCommitment),
+            Err(GkrError::ChallengeSplitMismatch {
+                layer: 1,
+                expected: 4,
+                actual: 3
+            })
+        );
+
+        let mut short_vk = vk.clone();
+        short_vk.layers[2].num_rounds = 4;
+        let mut short_rounds = prove_preprocessed(&mut circuit, &inputs, &MockCommitment);
+        short_rounds.proof.proof_polynomials[2].truncate(4);
+        assert_eq!(
+            verify_preprocessed(&short_rounds, &short_vk, &inputs, &MockCommitment),
+            Err(GkrError::ChallengeSplitMismatch {
+                layer: 2,
+                expected: 6,
+                actual: 4
+            })
+        );
+
+        let empty_vk = VerifierKey {
+            layers: Vec::new(),
+            ..vk
+        };
+        let preprocessed = prove_preprocessed(&mut circuit, &inputs, &MockCommitment);
+        assert_eq!(
+            verify_preprocessed(&preprocessed, &empty_vk, &inputs, &MockCommitment),
+            Err(GkrError::MalformedProof { layer: 0 })
+        );
+    }
+}
@@ -1,451 +1,4358 @@
-use crate::gkr_circuit::{Circuit, Layer, Operation};
+use crate::{
+    gkr_challenges::ChallengeStream,
+    gkr_circuit::{
+        monomial_wire_factors, output_layer_poly, pad_factors, tensor_add_mul_polynomials, Circuit,
+        CircuitTrace, Layer, LayerSource, Operation,
+    },
+    gkr_config::{FbcConstruction, GkrConfig, PaddingPolicy, SelectorEvaluation, VerifyMode},
+};
+
+#[cfg(feature = "lookup")]
+use crate::gkr_circuit::lookup_coefficients;
 
 use ark_ff::PrimeField;
 
-use fiat_shamir::fiat_shamir_transcript::{fq_vec_to_bytes, Transcript};
+use fiat_shamir::{
+    challenge_source::{ChallengeSource, FixedChallenges},
+    fiat_shamir_transcript::Transcript,
+};
 use multilinear_polynomial::{
     composed_polynomial::{ProductPoly, SumPoly},
     multilinear_polynomial_evaluation::MultilinearPoly,
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::io::Write;
+use std::time::{Duration, Instant};
 use sum_check::sum_check_protocol::{gkr_prove, gkr_verify};
+use univariate_polynomial::univariate_polynomial_dense::UnivariatePoly;
 
-#[derive(Debug)]
+/// Every element of a proof lives in the field `F` the circuit was proved
+/// over; challenges are drawn in `F` too, never in an extension of it, so
+/// there is no lifted part to separate from base-field checks.
+#[derive(Debug, PartialEq)]
 pub struct Proof<F: PrimeField> {
-    output_poly: MultilinearPoly<F>,
-    proof_polynomials: Vec<Vec<Vec<F>>>,
-    claimed_evaluations: Vec<(F, F)>,
+    pub(crate) output_poly: MultilinearPoly<F>,
+    pub(crate) proof_polynomials: Vec<Vec<Vec<F>>>,
+    pub(crate) claimed_evaluations: Vec<(F, F)>,
+    /// The claims skip connections make about the layer two below, one for
+    /// every layer with skip connections above the bottom two, top-down.
+    pub(crate) skip_evaluations: Vec<F>,
 }
 
-pub fn prove<F: PrimeField>(circuit: &mut Circuit<F>, inputs: &[F]) -> Proof<F> {
-    let mut transcript = Transcript::<F>::new();
-    let mut circuit_evaluations = circuit.evaluate(inputs);
-    let mut w_0 = circuit_evaluations.last().unwrap().to_vec();
+/// Proofs of several circuits over the same inputs, made one after another
+/// in a single transcript, with their claims about the inputs reduced to one
+/// evaluation of the inputs at a shared point.
+#[derive(Debug, PartialEq)]
+pub struct MultiProof<F: PrimeField> {
+    pub(crate) proofs: Vec<Proof<F>>,
+    /// Each circuit's `(o_1, o_2)`, the input poly at its bottom layer's
+    /// `r_b` and `r_c`.
+    pub(crate) input_claims: Vec<(F, F)>,
+    /// Round polys of the sum-check reducing every input claim to the input
+    /// poly at one point.
+    pub(crate) input_reduction: Vec<Vec<F>>,
+}
 
-    if w_0.len() == 1 {
-        w_0.push(F::zero());
+impl<F: PrimeField> MultiProof<F> {
+    pub fn proofs(&self) -> &[Proof<F>] {
+        &self.proofs
     }
-    let output_poly = MultilinearPoly::new(w_0);
+}
 
-    let (mut claimed_sum, random_challenge) = initiate_protocol(&mut transcript, &output_poly);
+#[cfg(feature = "profiling")]
+#[derive(Debug, Clone, Default)]
+pub struct ProveStats {
+    pub evaluation: Duration,
+    pub layers: Vec<LayerStats>,
+    /// Wall-clock time of the whole run, evaluation included.
+    pub total: Duration,
+    pub num_gates: usize,
+}
 
-    let num_layers = circuit.layers.len();
-    let mut proof_polys = Vec::with_capacity(num_layers);
-    let mut claimed_evaluations = Vec::with_capacity(num_layers.saturating_sub(1));
-    let mut current_rb = Vec::new();
-    let mut current_rc = Vec::new();
-    let mut alpha = F::zero();
-    let mut beta = F::zero();
-
-    circuit_evaluations.reverse();
-    let mut layers = circuit.layers.clone();
-    layers.reverse();
-
-    for (idx, layer) in layers.into_iter().enumerate() {
-        let w_i = if idx == num_layers - 1 {
-            inputs.to_vec()
-        } else {
-            circuit_evaluations[idx + 1].clone()
-        };
+#[cfg(feature = "profiling")]
+impl ProveStats {
+    /// Gates proved per second of `total`, the circuit evaluation included,
+    /// or `None` when no time was measured.
+    pub fn gates_per_second(&self) -> Option<f64> {
+        match self.total.is_zero() {
+            true => None,
+            false => Some(self.num_gates as f64 / self.total.as_secs_f64()),
+        }
+    }
+}
 
-        let fbc_poly = if idx == 0 {
-            get_fbc_poly(random_challenge, layer, &w_i, &w_i)
-        } else {
-            get_folded_fbc_poly(layer, &w_i, &w_i, &current_rb, &current_rc, alpha, beta)
-        };
+#[cfg(feature = "profiling")]
+#[derive(Debug, Clone, Default)]
+pub struct LayerStats {
+    pub fbc_construction: Duration,
+    pub sum_check: Duration,
+    pub transcript: Duration,
+}
 
-        let sum_check_proof = gkr_prove(claimed_sum, &fbc_poly, &mut transcript);
-        proof_polys.push(sum_check_proof.proof_polynomials);
+#[derive(Debug, Clone, Copy)]
+enum ProvePhase {
+    FbcConstruction,
+    SumCheck,
+    Transcript,
+}
 
-        if idx < num_layers - 1 {
-            let next_poly = MultilinearPoly::new(w_i);
-            let mid = sum_check_proof.random_challenges.len() / 2;
-            let (r_b, r_c) = sum_check_proof.random_challenges.split_at(mid);
+/// Fiat-Shamir challenges drawn during a protocol run: the output-layer
+/// challenge, every layer's sum-check challenges, the alpha/beta pair used
+/// to reduce each non-input layer's two claims and the weights of the skip
+/// connection claims in `Proof::skip_evaluations`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedChallenges<F: PrimeField> {
+    pub output_challenge: F,
+    pub layer_challenges: Vec<Vec<F>>,
+    pub alphas_betas: Vec<(F, F)>,
+    pub skip_weights: Vec<F>,
+}
 
-            let o_1 = next_poly.evaluate(r_b.to_vec());
-            let o_2 = next_poly.evaluate(r_c.to_vec());
-            current_rb = r_b.to_vec();
-            current_rc = r_c.to_vec();
+impl<F: PrimeField> VerifiedChallenges<F> {
+    /// The points `(r_b, r_c)` the input poly is evaluated at: the halves of
+    /// the bottom layer's sum-check challenges.
+    pub fn input_points(&self) -> (&[F], &[F]) {
+        let bottom = self
+            .layer_challenges
+            .last()
+            .map_or(&[][..], |challenges| challenges.as_slice());
 
-            transcript.append(&fq_vec_to_bytes(&[o_1]));
-            alpha = transcript.get_random_challenge();
+        debug_assert!(
+            bottom.len().is_multiple_of(2),
+            "{} input challenges",
+            bottom.len()
+        );
 
-            transcript.append(&fq_vec_to_bytes(&[o_2]));
-            beta = transcript.get_random_challenge();
+        bottom.split_at(bottom.len() / 2)
+    }
+}
 
-            claimed_sum = (alpha * o_1) + (beta * o_2);
-            claimed_evaluations.push((o_1, o_2));
-        }
+/// What `verify_with_mode` found for each layer it checked, top-down. Under
+/// `VerifyMode::FailFast` it stops at the first failing layer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+    pub layers: Vec<Result<(), GkrError>>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.layers.iter().all(Result::is_ok)
     }
 
-    Proof {
-        output_poly,
-        proof_polynomials: proof_polys,
-        claimed_evaluations,
+    pub fn failures(&self) -> Vec<GkrError> {
+        self.layers
+            .iter()
+            .filter_map(|result| result.clone().err())
+            .collect()
     }
 }
 
-pub fn verify<F: PrimeField>(proof: Proof<F>, mut circuit: Circuit<F>, inputs: &[F]) -> bool {
-    let mut transcript = Transcript::<F>::new();
-
-    let (mut current_claim, init_random_challenge) =
-        initiate_protocol(&mut transcript, &proof.output_poly);
+/// One layer's Fiat-Shamir challenges: its sum-check challenges, for every
+/// layer but the input layer the alpha/beta pair reducing its claims, and for
+/// a layer sending a skip connection claim the weight it is folded with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerChallenges<F: PrimeField> {
+    pub sum_check: Vec<F>,
+    pub alpha_beta: Option<(F, F)>,
+    pub skip_weight: Option<F>,
+}
 
-    let mut alpha = F::zero();
-    let mut beta = F::zero();
-    let mut prev_sumcheck_random_challenges = Vec::new();
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GkrError {
+    SumCheckFailed {
+        layer: usize,
+    },
+    /// The proof's shape does not fit the circuit: a layer is missing or has
+    /// the wrong number of round polys or claimed evaluations, a round poly
+    /// is not one evaluation longer than its layer's round degree, or the
+    /// output poly does not fit the output layer.
+    MalformedProof {
+        layer: usize,
+    },
+    ClaimMismatch {
+        layer: usize,
+    },
+    SelectorTooLarge {
+        layer: usize,
+        requested: usize,
+    },
+    InputLengthMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    OutputClaimMismatch {
+        index: usize,
+    },
+    ProofCountMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    /// A selector opening of a preprocessed proof does not match the
+    /// verifier key's commitment.
+    SelectorOpeningFailed {
+        layer: usize,
+    },
+    /// A field element of the proof's data for `layer` is not reduced below
+    /// the modulus; the output poly counts as the output layer's.
+    NonCanonicalElement {
+        layer: usize,
+    },
+    /// `layer`'s sum-check drew `actual` b and c challenges where its two
+    /// input wires need `expected`, so they cannot be split into `r_b` and
+    /// `r_c` of the layer's input-wire width.
+    ChallengeSplitMismatch {
+        layer: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// `layer` has lookup or custom gates, which only the sum-of-products fbc
+    /// poly has terms for, but the config asks for another construction, or
+    /// it has gates or skip connections a verifier key cannot hold.
+    UnsupportedConstruction {
+        layer: usize,
+    },
+    /// `layer` reads a skip connection from the inputs, which a verifier given
+    /// only claims about the inputs cannot evaluate.
+    InputsNotAvailable {
+        layer: usize,
+    },
+    /// The circuit cannot be split below its top `start_layer` layers, as
+    /// `Circuit::top_layers` reports.
+    InvalidSplit {
+        start_layer: usize,
+    },
+    /// The circuits' claims about their shared inputs do not reduce to the
+    /// inputs at one point.
+    InputClaimMismatch,
+}
 
-    circuit.layers.reverse();
-    let num_layers = circuit.layers.len();
+impl<F: PrimeField> Proof<F> {
+    pub fn output_poly(&self) -> &MultilinearPoly<F> {
+        &self.output_poly
+    }
 
-    for (i, layer) in circuit.layers.iter().enumerate() {
-        let sum_check_verify = gkr_verify(
-            proof.proof_polynomials[i].clone(),
-            current_claim,
-            &mut transcript,
-        );
+    /// Equality up to how round polys are written down: two round polys match
+    /// when they interpolate to the same polynomial once trailing zero
+    /// coefficients are trimmed, so one carrying an extra evaluation of the
+    /// same lower-degree polynomial still matches. Field elements already
+    /// compare by their canonical value.
+    pub fn structurally_eq(&self, other: &Self) -> bool {
+        let round_polys_eq = |a: &Vec<Vec<F>>, b: &Vec<Vec<F>>| {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| round_poly_coefficients(a) == round_poly_coefficients(b))
+        };
 
-        if !sum_check_verify.verified {
-            return false;
-        }
+        self.output_poly == other.output_poly
+            && self.claimed_evaluations == other.claimed_evaluations
+            && self.skip_evaluations == other.skip_evaluations
+            && self.proof_polynomials.len() == other.proof_polynomials.len()
+            && self
+                .proof_polynomials
+                .iter()
+                .zip(&other.proof_polynomials)
+                .all(|(a, b)| round_polys_eq(a, b))
+    }
 
-        let current_random_challenge = sum_check_verify.random_challenges;
+    /// The output-poly claim `(m_0, r)` the verifier starts from, where `r` is
+    /// the output challenge and `m_0` the output poly evaluated at it.
+    pub fn output_claim(&self) -> (F, F) {
+        initiate_protocol(&mut Transcript::new(), &self.output_poly)
+    }
 
-        let (o_1, o_2) = if i == num_layers - 1 {
-            evaluate_input_poly(inputs, &current_random_challenge)
-        } else {
-            proof.claimed_evaluations[i]
-        };
+    /// Replays the verifier's transcript to derive every layer's challenges
+    /// without evaluating any selectors or checking any claims. Only the
+    /// proof's shape is checked, as `verify_structure` does.
+    pub fn derive_challenges(
+        &self,
+        circuit: &Circuit<F, impl LayerSource<F>>,
+    ) -> Result<Vec<LayerChallenges<F>>, GkrError> {
+        self.derive_challenges_in(circuit, &mut Transcript::new())
+    }
 
-        let expected_claim = if i == 0 {
-            get_verifier_claim(
-                layer,
-                init_random_challenge,
-                &current_random_challenge,
-                o_1,
-                o_2,
-            )
-        } else {
-            get_folded_verifier_claim(
-                layer,
-                &current_random_challenge,
-                &prev_sumcheck_random_challenges,
-                o_1,
-                o_2,
-                alpha,
-                beta,
-            )
-        };
+    /// Like `derive_challenges`, replaying from `source` in the state the
+    /// prover's transcript was in, e.g. for `prove_with_transcript` proofs.
+    pub fn derive_challenges_in(
+        &self,
+        circuit: &Circuit<F, impl LayerSource<F>>,
+        source: &mut impl ChallengeSource<F>,
+    ) -> Result<Vec<LayerChallenges<F>>, GkrError> {
+        ProofShape::of(circuit, FbcConstruction::default()).check(self)?;
 
-        if expected_claim != sum_check_verify.final_claimed_sum {
-            return false;
-        }
+        let mut stream = ChallengeStream::new(source);
+        stream.output_claim(&self.output_poly);
 
-        prev_sumcheck_random_challenges = current_random_challenge;
+        let num_layers = circuit.depth();
+        let mut challenges = Vec::with_capacity(num_layers);
+        let mut skip_evaluations = self.skip_evaluations.iter();
 
-        transcript.append(&fq_vec_to_bytes(&[o_1]));
-        alpha = transcript.get_random_challenge();
+        for (i, layer) in circuit.layers_top_down().enumerate() {
+            stream.start_layer(i);
 
-        transcript.append(&fq_vec_to_bytes(&[o_2]));
-        beta = transcript.get_random_challenge();
+            let mut sum_check = Vec::with_capacity(self.proof_polynomials[i].len());
+            for round_poly in &self.proof_polynomials[i] {
+                sum_check.push(stream.next_layer_challenge(round_poly));
+            }
 
-        current_claim = (alpha * o_1) + (beta * o_2);
-    }
+            let alpha_beta = if i < num_layers - 1 {
+                let (o_1, o_2) = self.claimed_evaluations[i];
 
-    true
-}
+                Some(stream.reduce(o_1, o_2))
+            } else {
+                None
+            };
 
-fn initiate_protocol<F: PrimeField>(
-    transcript: &mut Transcript<F>,
-    output_poly: &MultilinearPoly<F>,
-) -> (F, F) {
-    transcript.append(&fq_vec_to_bytes(&output_poly.evaluation));
+            let skip_weight = sends_skip_evaluation(&layer, i, num_layers).then(|| {
+                let o_3 = skip_evaluations
+                    .next()
+                    .expect("every layer sending a skip claim has a skip evaluation");
 
-    let random_challenge = transcript.get_random_challenge();
-    let m_0 = output_poly.evaluate(vec![random_challenge]);
+                stream.skip_weight(*o_3)
+            });
 
-    transcript.append(&fq_vec_to_bytes(&[m_0]));
+            challenges.push(LayerChallenges {
+                sum_check,
+                alpha_beta,
+                skip_weight,
+            });
+        }
 
-    (m_0, random_challenge)
+        Ok(challenges)
+    }
 }
 
-pub fn tensor_add_mul_polynomials<F: PrimeField>(
-    poly_a: &[F],
-    poly_b: &[F],
-    op: Operation,
-) -> MultilinearPoly<F> {
-    let new_eval: Vec<F> = poly_a
-        .iter()
-        .flat_map(|a| poly_b.iter().map(move |b| op.apply(*a, *b)))
-        .collect();
+pub fn prove<F: PrimeField>(circuit: &mut Circuit<F>, inputs: &[F]) -> Proof<F> {
+    let mut transcript = Transcript::<F>::new();
 
-    MultilinearPoly::new(new_eval)
+    prove_with_transcript(circuit, inputs, &mut transcript)
 }
 
-pub fn get_fbc_poly<F: PrimeField>(
-    random_challenge: F,
-    layer: Layer<F>,
-    w_b: &[F],
-    w_c: &[F],
-) -> SumPoly<F> {
-    let add_i = layer
-        .get_add_mul_i(Operation::Add)
-        .partial_evaluate(0, &random_challenge);
-    let mul_i = layer
-        .get_add_mul_i(Operation::Mul)
-        .partial_evaluate(0, &random_challenge);
+/// Pads the inputs to the circuit's input width according to
+/// `config.padding` and checks every selector fits `config.max_selector_size`
+/// before proving.
+pub fn prove_with_config<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+    config: &GkrConfig,
+) -> Result<Proof<F>, GkrError> {
+    let inputs = apply_config(circuit, inputs, config)?;
 
-    let summed_w_poly = tensor_add_mul_polynomials(w_b, w_c, Operation::Add);
-    let multiplied_w_poly = tensor_add_mul_polynomials(w_b, w_c, Operation::Mul);
+    Ok(prove_with_construction(
+        circuit,
+        &inputs,
+        &mut Transcript::new(),
+        config.fbc_construction,
+    ))
+}
 
-    let add_eval_product = ProductPoly::new(vec![add_i.evaluation, summed_w_poly.evaluation]);
-    let mul_eval_product = ProductPoly::new(vec![mul_i.evaluation, multiplied_w_poly.evaluation]);
+/// Proves like `prove`, but zero-pads inputs shorter than the circuit's
+/// input width instead of requiring them to fit, logging a warning when it
+/// does. The proof is for the padded inputs, so verify it against those or
+/// with `verify_with_config` under the default zero-padding policy.
+pub fn prove_padding<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+) -> Result<Proof<F>, GkrError> {
+    let width = circuit.input_width();
+    let config = GkrConfig {
+        padding: PaddingPolicy::ZeroPad,
+        ..GkrConfig::default()
+    };
+    let proof = prove_with_config(circuit, inputs, &config)?;
 
-    SumPoly::new(vec![add_eval_product, mul_eval_product])
+    if inputs.len() < width {
+        log::warn!(
+            "zero-padding {} inputs to the circuit's input width {width}",
+            inputs.len()
+        );
+    }
+
+    Ok(proof)
 }
 
-fn get_folded_fbc_poly<F: PrimeField>(
-    layer: Layer<F>,
-    w_b: &[F],
-    w_c: &[F],
-    r_b: &[F],
-    r_c: &[F],
-    alpha: F,
-    beta: F,
-) -> SumPoly<F> {
-    let add_i = layer.get_add_mul_i(Operation::Add);
-    let mul_i = layer.get_add_mul_i(Operation::Mul);
+/// Proves only the top `start_layer` layers, taking `claimed_intermediate`
+/// as the outputs of the layer below them. Nothing ties the claim to the
+/// circuit's inputs, so `verify_from_layer` only shows the top layers map
+/// it to the outputs; a proof of `Circuit::bottom_layers` shows the rest.
+/// Unless a skip connection reads across the split, the top `start_layer`
+/// layers of a full proof are exactly this proof, as their transcripts agree
+/// up to there.
+pub fn prove_from_layer<F: PrimeField>(
+    circuit: &Circuit<F>,
+    start_layer: usize,
+    claimed_intermediate: &[F],
+) -> Result<Proof<F>, GkrError> {
+    let mut top = circuit
+        .top_layers(start_layer)
+        .map_err(|_| GkrError::InvalidSplit { start_layer })?;
 
-    let summed_add_i = add_i.multi_partial_evaluate(r_b).scale(alpha)
-        + add_i.multi_partial_evaluate(r_c).scale(beta);
+    Ok(prove(&mut top, claimed_intermediate))
+}
 
-    let summed_mul_i = mul_i.multi_partial_evaluate(r_b).scale(alpha)
-        + mul_i.multi_partial_evaluate(r_c).scale(beta);
+/// Proves against an ongoing transcript so the GKR challenges chain with an
+/// outer protocol.
+pub fn prove_with_transcript<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+    transcript: &mut Transcript<F, impl Write>,
+) -> Proof<F> {
+    prove_interactive(circuit, inputs, transcript)
+}
 
-    let summed_w_poly = tensor_add_mul_polynomials(w_b, w_c, Operation::Add);
-    let multiplied_w_poly = tensor_add_mul_polynomials(w_b, w_c, Operation::Mul);
+/// Proves over a Keccak transcript that absorbs `seed` first, so a given
+/// seed, circuit and inputs always give the same proof bytes. Verify with
+/// `verify_deterministic` and the same seed.
+pub fn prove_deterministic<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+    seed: &[u8],
+) -> Proof<F> {
+    let mut transcript = Transcript::new();
+    transcript.append(seed);
 
-    let add_product_poly =
-        ProductPoly::new(vec![summed_add_i.evaluation, summed_w_poly.evaluation]);
-    let mul_product_poly =
-        ProductPoly::new(vec![summed_mul_i.evaluation, multiplied_w_poly.evaluation]);
+    prove_with_transcript(circuit, inputs, &mut transcript)
+}
 
-    SumPoly::new(vec![add_product_poly, mul_product_poly])
+/// Proves with an externally supplied `nonce` absorbed before anything else,
+/// so every challenge depends on it and a prover cannot grind challenges by
+/// retrying paddings before the nonce is known. The nonce follows a
+/// `gkr_nonce` phase marker carrying its length, so a nonce never absorbs
+/// like a `prove_deterministic` seed or like a shorter nonce.
+pub fn prove_with_nonce<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+    nonce: &[u8],
+) -> Proof<F> {
+    prove_with_transcript(circuit, inputs, &mut nonce_transcript(nonce))
 }
 
-fn get_verifier_claim<F: PrimeField>(
-    layer: &Layer<F>,
-    init_random_challenge: F,
-    sumcheck_random_challenges: &[F],
-    o_1: F,
-    o_2: F,
-) -> F {
-    let mut all_random_challenges = Vec::with_capacity(1 + sumcheck_random_challenges.len());
+fn nonce_transcript<F: PrimeField>(nonce: &[u8]) -> Transcript<F> {
+    let mut transcript = Transcript::new();
+    transcript.absorb_phase("gkr_nonce", nonce.len());
+    transcript.append(nonce);
 
-    all_random_challenges.push(init_random_challenge);
-    all_random_challenges.extend_from_slice(sumcheck_random_challenges);
+    transcript
+}
 
-    let a_r = layer
-        .get_add_mul_i(Operation::Add)
-        .evaluate(all_random_challenges.clone());
-    let m_r = layer
-        .get_add_mul_i(Operation::Mul)
-        .evaluate(all_random_challenges);
+/// Proves every circuit on `inputs` in one transcript that absorbs the
+/// inputs once up front, so each proof's challenges also depend on the
+/// inputs and every proof before it. Each circuit ends with claims about the
+/// inputs at its own `r_b` and `r_c`; these are folded with transcript
+/// weights into one claim that a final sum-check reduces to the inputs at a
+/// single point shared by all circuits.
+pub fn prove_shared_input<F: PrimeField>(circuits: &[Circuit<F>], inputs: &[F]) -> MultiProof<F> {
+    let mut transcript = Transcript::<F>::new();
+    transcript.append_fields(inputs);
 
-    (a_r * (o_1 + o_2)) + (m_r * (o_1 * o_2))
-}
+    let mut proofs = Vec::with_capacity(circuits.len());
+    let mut input_claims = Vec::with_capacity(circuits.len());
+    let mut input_points = Vec::with_capacity(2 * circuits.len());
 
-fn get_folded_verifier_claim<F: PrimeField>(
-    layer: &Layer<F>,
-    current_random_challenge: &[F],
-    previous_random_challenge: &[F],
-    o_1: F,
-    o_2: F,
-    alpha: F,
-    beta: F,
-) -> F {
-    let (prev_r_b, prev_r_c) =
-        previous_random_challenge.split_at(previous_random_challenge.len() / 2);
+    for circuit in circuits {
+        let mut replay = transcript.clone();
+        let proof = prove_interactive(&mut circuit.clone(), inputs, &mut transcript);
+        let challenges = proof
+            .derive_challenges_in(circuit, &mut replay)
+            .expect("a proof fresh from `prove_interactive` has the circuit's shape");
+        let bottom = &challenges
+            .last()
+            .expect("every circuit has a layer")
+            .sum_check;
 
-    let add_i = layer.get_add_mul_i(Operation::Add);
-    let mul_i = layer.get_add_mul_i(Operation::Mul);
+        let (r_b, r_c) = bottom.split_at(bottom.len() / 2);
+        input_points.extend([r_b.to_vec(), r_c.to_vec()]);
+        input_claims.push(evaluate_input_poly(inputs, bottom));
+        proofs.push(proof);
+    }
 
-    let summed_add_i = add_i.multi_partial_evaluate(prev_r_b).scale(alpha)
-        + add_i.multi_partial_evaluate(prev_r_c).scale(beta);
+    let weights = input_claim_weights(&mut transcript, &input_claims);
+    let claim = fold_input_claims(&weights, &input_claims);
 
-    let summed_mul_i = mul_i.multi_partial_evaluate(prev_r_b).scale(alpha)
-        + mul_i.multi_partial_evaluate(prev_r_c).scale(beta);
+    let mut folded_eq = vec![F::zero(); inputs.len()];
+    for (weight, point) in weights.iter().zip(&input_points) {
+        for (acc, eq) in folded_eq.iter_mut().zip(eq_table(point)) {
+            *acc += *weight * eq;
+        }
+    }
 
-    let a_r = summed_add_i.evaluate(current_random_challenge.to_vec());
-    let m_r = summed_mul_i.evaluate(current_random_challenge.to_vec());
+    let reduction_poly = SumPoly::new(vec![ProductPoly::new(vec![folded_eq, inputs.to_vec()])]);
+    let input_reduction = gkr_prove(claim, &reduction_poly, &mut transcript).proof_polynomials;
 
-    (a_r * (o_1 + o_2)) + (m_r * (o_1 * o_2))
+    MultiProof {
+        proofs,
+        input_claims,
+        input_reduction,
+    }
 }
 
-fn evaluate_input_poly<F: PrimeField>(inputs: &[F], sumcheck_random_challenges: &[F]) -> (F, F) {
+pub fn verify_shared_input<F: PrimeField>(
+    proof: MultiProof<F>,
+    circuits: &[Circuit<F>],
+    inputs: &[F],
+) -> Result<(), GkrError> {
     let input_poly = MultilinearPoly::new(inputs.to_vec());
 
-    let (r_b, r_c) = sumcheck_random_challenges.split_at(sumcheck_random_challenges.len() / 2);
+    verify_shared_input_with(proof, circuits, inputs, |point| {
+        input_poly.evaluate(point.to_vec())
+    })
+}
 
-    let o_1 = input_poly.evaluate(r_b.to_vec());
-    let o_2 = input_poly.evaluate(r_c.to_vec());
+/// Verifies like `verify_shared_input`, reading the input poly only through
+/// `evaluate_inputs`, at the one point all circuits' input claims reduce to.
+fn verify_shared_input_with<F: PrimeField>(
+    proof: MultiProof<F>,
+    circuits: &[Circuit<F>],
+    inputs: &[F],
+    evaluate_inputs: impl Fn(&[F]) -> F,
+) -> Result<(), GkrError> {
+    if proof.proofs.len() != circuits.len() || proof.input_claims.len() != circuits.len() {
+        return Err(GkrError::ProofCountMismatch {
+            expected: circuits.len(),
+            actual: proof.proofs.len().min(proof.input_claims.len()),
+        });
+    }
 
-    (o_1, o_2)
-}
+    if inputs.len() != 1 << proof.input_reduction.len() {
+        return Err(GkrError::InputClaimMismatch);
+    }
 
-#[cfg(test)]
-mod test {
-    use super::{
-        get_fbc_poly, get_folded_fbc_poly, prove, tensor_add_mul_polynomials, verify, Proof,
-    };
-    use crate::gkr_circuit::{Circuit, Gate, Layer, Operation};
-    use ark_bn254::Fq;
-    use multilinear_polynomial::{
-        composed_polynomial::{ProductPoly, SumPoly},
-        multilinear_polynomial_evaluation::MultilinearPoly,
-    };
+    let mut transcript = Transcript::<F>::new();
+    transcript.append_fields(inputs);
 
-    #[test]
-    fn it_add_polys_correctly() {
-        let poly_a = &[Fq::from(0), Fq::from(2)];
-        let poly_b = &[Fq::from(0), Fq::from(3)];
+    let mut input_points = Vec::with_capacity(2 * circuits.len());
 
-        let expected_poly = vec![Fq::from(0), Fq::from(3), Fq::from(2), Fq::from(5)];
+    for ((proof, circuit), claims) in proof.proofs.iter().zip(circuits).zip(&proof.input_claims) {
+        if inputs.len() != circuit.input_width() {
+            return Err(GkrError::InputLengthMismatch {
+                expected: circuit.input_width(),
+                actual: inputs.len(),
+            });
+        }
 
-        let result = tensor_add_mul_polynomials(poly_a, poly_b, Operation::Add);
+        let verified = verify_with_input_claims(proof, circuit, *claims, &mut transcript)?;
+        let (r_b, r_c) = verified.input_points();
+        input_points.extend([r_b.to_vec(), r_c.to_vec()]);
+    }
 
-        assert_eq!(result.evaluation, expected_poly);
+    let weights = input_claim_weights(&mut transcript, &proof.input_claims);
+    let claim = fold_input_claims(&weights, &proof.input_claims);
 
-        let poly_a = &[Fq::from(0), Fq::from(3)];
-        let poly_b = &[Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(2)];
+    let reduction = gkr_verify(
+        proof.input_reduction,
+        claim,
+        INPUT_REDUCTION_DEGREE,
+        &mut transcript,
+        |point| {
+            let folded_eq: F = weights
+                .iter()
+                .zip(&input_points)
+                .map(|(weight, input_point)| *weight * eq_at(input_point, point))
+                .sum();
 
-        let expected_poly = vec![
-            Fq::from(0),
-            Fq::from(0),
-            Fq::from(0),
-            Fq::from(2),
-            Fq::from(3),
-            Fq::from(3),
-            Fq::from(3),
-            Fq::from(5),
-        ];
+            folded_eq * evaluate_inputs(point)
+        },
+    );
+
+    match reduction.verified {
+        true => Ok(()),
+        false => Err(GkrError::InputClaimMismatch),
+    }
+}
 
-        let result = tensor_add_mul_polynomials(poly_a, poly_b, Operation::Add);
+/// Degree of the input reduction's round polys: the folded eq poly times the
+/// input poly.
+const INPUT_REDUCTION_DEGREE: usize = 2;
 
-        assert_eq!(result.evaluation, expected_poly);
+/// Absorbs every circuit's input claims and draws one weight per claim, `r_b`
+/// before `r_c`, circuit by circuit.
+fn input_claim_weights<F: PrimeField>(
+    transcript: &mut Transcript<F>,
+    input_claims: &[(F, F)],
+) -> Vec<F> {
+    for (o_1, o_2) in input_claims {
+        transcript.append_fields(&[*o_1, *o_2]);
     }
 
-    #[test]
-    fn it_multiplies_polys_correctly() {
-        let poly_a = &[Fq::from(0), Fq::from(2)];
-        let poly_b = &[Fq::from(0), Fq::from(3)];
+    transcript.get_random_challenges(2 * input_claims.len())
+}
 
-        let expected_poly = vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(6)];
+fn fold_input_claims<F: PrimeField>(weights: &[F], input_claims: &[(F, F)]) -> F {
+    input_claims
+        .iter()
+        .flat_map(|(o_1, o_2)| [*o_1, *o_2])
+        .zip(weights)
+        .map(|(claim, weight)| claim * weight)
+        .sum()
+}
 
-        let result = tensor_add_mul_polynomials(poly_a, poly_b, Operation::Mul);
+/// `eq(a, b)` for field points `a` and `b` of the same length.
+fn eq_at<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| *a * b + (F::one() - a) * (F::one() - b))
+        .product()
+}
 
-        assert_eq!(result.evaluation, expected_poly);
+/// Proves with challenges drawn from `challenges` instead of a fresh
+/// transcript, e.g. a fixed adversarial sequence in soundness tests.
+pub fn prove_interactive<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+    challenges: &mut impl ChallengeSource<F>,
+) -> Proof<F> {
+    prove_with_construction(circuit, inputs, challenges, FbcConstruction::default())
+}
 
-        let poly_a = &[Fq::from(0), Fq::from(3)];
-        let poly_b = &[Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(2)];
+fn prove_with_construction<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+    challenges: &mut impl ChallengeSource<F>,
+    construction: FbcConstruction,
+) -> Proof<F> {
+    prove_traced(circuit, inputs, challenges, construction).0
+}
 
-        let expected_poly = vec![
-            Fq::from(0),
-            Fq::from(0),
-            Fq::from(0),
-            Fq::from(0),
-            Fq::from(0),
-            Fq::from(0),
-            Fq::from(0),
-            Fq::from(6),
-        ];
+/// Proves like `prove` and also returns every layer's evaluations as
+/// `Circuit::evaluate` returns them, since proving computes them anyway.
+pub fn prove_with_trace<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+) -> (Proof<F>, CircuitTrace<F>) {
+    let mut transcript = Transcript::<F>::new();
 
-        let result = tensor_add_mul_polynomials(poly_a, poly_b, Operation::Mul);
+    prove_traced(circuit, inputs, &mut transcript, FbcConstruction::default())
+}
 
-        assert_eq!(result.evaluation, expected_poly);
-    }
+fn prove_traced<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+    challenges: &mut impl ChallengeSource<F>,
+    construction: FbcConstruction,
+) -> (Proof<F>, CircuitTrace<F>) {
+    let trace = circuit.evaluate(inputs);
 
-    #[test]
-    fn test_get_fbc_poly() {
-        let gate = Gate::new(Fq::from(2), Fq::from(14), Operation::Add);
+    let (proof, _) = prove_layers(
+        circuit,
+        trace.output().to_vec(),
+        challenges,
+        construction,
+        |idx| trace.layer_below(idx).to_vec(),
+        &mut |_, _, _| {},
+    );
 
-        let layer = Layer::new(vec![gate]);
+    (proof, trace)
+}
 
-        let r_c = Fq::from(5);
+#[cfg(feature = "profiling")]
+pub fn prove_with_stats<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+) -> (Proof<F>, ProveStats) {
+    let mut transcript = Transcript::<F>::new();
+    let mut stats = ProveStats {
+        layers: vec![LayerStats::default(); circuit.depth()],
+        num_gates: circuit.layers.iter().map(|layer| layer.gates.len()).sum(),
+        ..Default::default()
+    };
 
-        let w_1_poly = &[Fq::from(2), Fq::from(12)];
+    let start = Instant::now();
+    let trace = circuit.evaluate(inputs);
+    stats.evaluation = start.elapsed();
 
-        let add_i_r =
-            MultilinearPoly::new(vec![Fq::from(0), Fq::from(-4), Fq::from(0), Fq::from(0)]);
+    let (proof, _) = prove_layers(
+        circuit,
+        trace.output().to_vec(),
+        &mut transcript,
+        FbcConstruction::default(),
+        |idx| trace.layer_below(idx).to_vec(),
+        &mut |layer, phase, elapsed| {
+            let layer_stats = &mut stats.layers[layer];
+            match phase {
+                ProvePhase::FbcConstruction => layer_stats.fbc_construction += elapsed,
+                ProvePhase::SumCheck => layer_stats.sum_check += elapsed,
+                ProvePhase::Transcript => layer_stats.transcript += elapsed,
+            }
+        },
+    );
+    stats.total = start.elapsed();
 
-        let mul_i_r = MultilinearPoly::new(vec![Fq::from(0); 4]);
+    (proof, stats)
+}
 
-        let fbc_poly = get_fbc_poly(r_c, layer, w_1_poly, w_1_poly);
+/// Same proof as `prove`, but only one layer's values are held at a time.
+/// Each layer below is re-evaluated from the inputs when needed, so memory is
+/// O(width) instead of O(width * depth) at the cost of O(depth^2) gate evaluations.
+pub fn prove_low_memory<F: PrimeField>(
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+) -> Proof<F> {
+    let num_layers = circuit.depth();
+    let w_0 = circuit.evaluate_layer(inputs, num_layers - 1);
+    let mut transcript = Transcript::<F>::new();
 
-        let one = ProductPoly::new(vec![
-            add_i_r.evaluation,
-            vec![Fq::from(4), Fq::from(14), Fq::from(14), Fq::from(24)],
-        ]);
+    prove_layers(
+        circuit,
+        w_0,
+        &mut transcript,
+        FbcConstruction::default(),
+        |idx| {
+            if idx == num_layers - 1 {
+                inputs.to_vec()
+            } else {
+                circuit.evaluate_layer(inputs, num_layers - idx - 2)
+            }
+        },
+        &mut |_, _, _| {},
+    )
+    .0
+}
 
-        let two = ProductPoly::new(vec![
-            mul_i_r.evaluation,
-            vec![Fq::from(4), Fq::from(24), Fq::from(24), Fq::from(144)],
-        ]);
+/// Values feeding the layer at top-down index `idx`, given the layer
+/// evaluations in top-down order: the inputs for the bottom layer, otherwise
+/// the evaluations of the next layer down.
+pub fn layer_below<'a, F: PrimeField>(
+    evaluations: &'a [Vec<F>],
+    inputs: &'a [F],
+    idx: usize,
+    num_layers: usize,
+) -> &'a [F] {
+    if evaluations.len() != num_layers {
+        panic!("expected one evaluation per layer");
+    }
 
-        let expected_result = SumPoly::new(vec![one, two]);
+    if idx >= num_layers {
+        panic!("layer index {idx} out of bounds for {num_layers} layers");
+    }
 
-        assert_eq!(fbc_poly.polys, expected_result.polys);
+    if idx == num_layers - 1 {
+        inputs
+    } else {
+        &evaluations[idx + 1]
     }
+}
 
-    // #[test]
-    // fn test_get_folded_fbc_poly() {
-    //     let gate_1 = Gate::new(Fq::from(1), Fq::from(2), Operation::Mul);
-    //     let gate_2 = Gate::new(Fq::from(3), Fq::from(4), Operation::Mul);
+fn prove_layers<F: PrimeField>(
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    w_0: Vec<F>,
+    transcript: &mut impl ChallengeSource<F>,
+    construction: FbcConstruction,
+    mut get_layer_below: impl FnMut(usize) -> Vec<F>,
+    record: &mut dyn FnMut(usize, ProvePhase, Duration),
+) -> (Proof<F>, VerifiedChallenges<F>) {
+    let output_poly = output_layer_poly(w_0);
+    let mut stream = ChallengeStream::new(transcript);
 
-    //     let layer = Layer::new(vec![gate_1, gate_2]);
+    let start = Instant::now();
+    let (mut claimed_sum, random_challenge) = stream.output_claim(&output_poly);
+    record(0, ProvePhase::Transcript, start.elapsed());
 
-    //     let w_poly = vec![Fq::from(1), Fq::from(2), Fq::from(3), Fq::from(4)];
+    let num_layers = circuit.depth();
+    let mut proof_polys = Vec::with_capacity(num_layers);
+    let mut claimed_evaluations = Vec::with_capacity(num_layers.saturating_sub(1));
+    let mut skip_evaluations = Vec::new();
+    let mut fold: Option<ClaimFold<F>> = None;
+    let mut pending_skip: Option<SkipClaim<F>> = None;
+    let mut challenges = VerifiedChallenges {
+        output_challenge: random_challenge,
+        layer_challenges: Vec::with_capacity(num_layers),
+        alphas_betas: Vec::with_capacity(num_layers.saturating_sub(1)),
+        skip_weights: Vec::new(),
+    };
 
-    //     let alpha = Fq::from(2);
-    //     let beta = Fq::from(1);
+    for (idx, layer) in circuit.layers_top_down().enumerate() {
+        stream.start_layer(idx);
 
-    //     let r_b = &[Fq::from(2)];
-    //     let r_c = &[Fq::from(3)];
+        let w_i = get_layer_below(idx);
+        let w_two_below = match layer.has_skip_connections() {
+            true => get_layer_below(idx + 1),
+            false => Vec::new(),
+        };
+        let (w_b, w_c) = layer.wire_tables(&w_i, &w_two_below);
+        let num_rounds = layer.num_sumcheck_rounds() + construction.extra_rounds();
+        let round_degree = layer.round_degree();
 
-    //     let folded_fbc_poly = get_folded_fbc_poly(layer, &w_poly, &w_poly, r_b, r_c, alpha, beta);
+        let start = Instant::now();
+        #[cfg(feature = "lookup")]
+        let lookup_terms = layer.has_lookups().then(|| {
+            assert_eq!(
+                construction,
+                FbcConstruction::SumOfProducts,
+                "lookup gates need the sum-of-products fbc poly"
+            );
 
-    // }
+            get_lookup_terms(&layer, random_challenge, fold.as_ref(), &w_b, &w_c)
+        });
 
-    #[test]
-    fn test_valid_proving_and_verification() {
-        let circuit_structure: Vec<Vec<Operation>> = vec![
-            vec![
-                Operation::Mul,
-                Operation::Mul,
-                Operation::Mul,
-                Operation::Mul,
-            ],
-            vec![Operation::Add, Operation::Add],
-            vec![Operation::Add],
-        ];
+        let custom_terms = layer.has_custom_gates().then(|| {
+            assert_eq!(
+                construction,
+                FbcConstruction::SumOfProducts,
+                "custom gates need the sum-of-products fbc poly"
+            );
 
-        let inputs: Vec<Fq> = vec![
-            Fq::from(5),
-            Fq::from(2),
-            Fq::from(2),
-            Fq::from(4),
-            Fq::from(10),
-            Fq::from(0),
-            Fq::from(3),
-            Fq::from(3),
-        ];
+            get_custom_terms(&layer, random_challenge, fold.as_ref(), &w_b, &w_c)
+        });
 
-        let mut circuit = Circuit::new(circuit_structure);
+        let fbc_poly = match (construction, &fold) {
+            (FbcConstruction::SumOfProducts, None) => {
+                get_fbc_poly(random_challenge, layer.clone(), &w_b, &w_c)
+            }
+            (FbcConstruction::SumOfProducts, Some(fold)) => {
+                get_folded_fbc_poly(&layer, &w_b, &w_c, &fold.weights())
+            }
+            (FbcConstruction::SingleProduct, _) => {
+                let (add_i, mul_i) = match &fold {
+                    None => get_output_selectors(&layer, random_challenge),
+                    Some(fold) => fold_fbc_selectors(&layer, &fold.weights()),
+                };
 
-        let proof = prove(&mut circuit, &inputs);
+                SumPoly::new(vec![get_fbc_poly_single_product(add_i, mul_i, &w_b, &w_c)])
+            }
+        };
 
-        let is_verified = verify(proof, circuit, &inputs);
+        #[cfg(feature = "lookup")]
+        let fbc_poly = match lookup_terms {
+            Some(lookup_terms) => {
+                let mut polys = fbc_poly.polys;
+                polys.extend(lookup_terms);
+                SumPoly::new(polys)
+            }
+            None => fbc_poly,
+        };
+
+        let fbc_poly = match custom_terms {
+            Some(custom_terms) => {
+                let mut polys = fbc_poly.polys;
+                polys.extend(custom_terms);
+                SumPoly::new(pad_factors(polys, round_degree))
+            }
+            None => fbc_poly,
+        };
+
+        record(idx, ProvePhase::FbcConstruction, start.elapsed());
 
-        assert_eq!(is_verified, true);
+        let start = Instant::now();
+        let sum_check_proof = gkr_prove(claimed_sum, &fbc_poly, &mut stream);
+        assert_eq!(
+            sum_check_proof.random_challenges.len(),
+            num_rounds,
+            "sum-check of layer {idx} ran an unexpected number of rounds"
+        );
+        proof_polys.push(sum_check_proof.proof_polynomials);
+        record(idx, ProvePhase::SumCheck, start.elapsed());
+
+        if idx < num_layers - 1 {
+            let next_poly = MultilinearPoly::new(w_i);
+            let bc_challenges = &sum_check_proof.random_challenges[construction.extra_rounds()..];
+            let (r_b, r_c) = claim_points(&layer, bc_challenges);
+
+            let o_1 = next_poly.evaluate(r_b.to_vec());
+            let o_2 = next_poly.evaluate(r_c.to_vec());
+            let o_3 = sends_skip_evaluation(&layer, idx, num_layers).then(|| {
+                MultilinearPoly::new(w_two_below).evaluate(skip_point(bc_challenges).to_vec())
+            });
+
+            let start = Instant::now();
+            let (alpha, beta) = stream.reduce(o_1, o_2);
+
+            let skip = o_3.map(|o_3| SkipClaim {
+                gamma: stream.skip_weight(o_3),
+                point: skip_point(bc_challenges).to_vec(),
+                evaluation: o_3,
+            });
+            record(idx, ProvePhase::Transcript, start.elapsed());
+
+            let next_fold = ClaimFold {
+                r_b: r_b.to_vec(),
+                r_c: r_c.to_vec(),
+                alpha,
+                beta,
+                skip: pending_skip.take(),
+            };
+
+            claimed_sum = next_fold.claim((o_1, o_2));
+            claimed_evaluations.push((o_1, o_2));
+            challenges.alphas_betas.push((alpha, beta));
+            if let Some(skip) = &skip {
+                skip_evaluations.push(skip.evaluation);
+                challenges.skip_weights.push(skip.gamma);
+            }
+            fold = Some(next_fold);
+            pending_skip = skip;
+        }
+
+        challenges
+            .layer_challenges
+            .push(sum_check_proof.random_challenges);
+    }
+
+    let proof = Proof {
+        output_poly,
+        proof_polynomials: proof_polys,
+        claimed_evaluations,
+        skip_evaluations,
+    };
+
+    (proof, challenges)
+}
+
+pub fn verify<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+) -> bool {
+    let mut transcript = Transcript::<F>::new();
+
+    verify_in_transcript(proof, circuit, inputs, &mut transcript).is_ok()
+}
+
+/// Checks only the proof's shape against `circuit` for `construction`, as
+/// the verifier does before verifying. No field arithmetic is done,
+/// so malformed proofs are rejected before paying for a full `verify`.
+pub fn verify_structure<F: PrimeField>(
+    proof: &Proof<F>,
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    construction: FbcConstruction,
+) -> Result<(), GkrError> {
+    ProofShape::of(circuit, construction).check(proof)
+}
+
+/// Verifies a `prove_from_layer` proof of the top `start_layer` layers
+/// against `claimed_intermediate`, the outputs of the layer below them.
+pub fn verify_from_layer<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    start_layer: usize,
+    claimed_intermediate: &[F],
+) -> Result<(), GkrError> {
+    let top = circuit
+        .top_layers(start_layer)
+        .map_err(|_| GkrError::InvalidSplit { start_layer })?;
+
+    verify_in_transcript(proof, top, claimed_intermediate, &mut Transcript::new())
+}
+
+/// Applies the same checks and padding policy as `prove_with_config` before
+/// verifying.
+pub fn verify_with_config<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+    config: &GkrConfig,
+) -> Result<(), GkrError> {
+    let inputs = apply_config(&circuit, inputs, config)?;
+
+    verify_layers(
+        &proof,
+        &circuit,
+        InputSource::Inputs(&inputs),
+        &mut Transcript::new(),
+        config.fbc_construction,
+        config.selector_evaluation,
+        &mut |_| {},
+    )
+    .map(|_| ())
+}
+
+/// Layers are indexed top-down, as in the other `GkrError`s.
+fn apply_config<F: PrimeField>(
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+    config: &GkrConfig,
+) -> Result<Vec<F>, GkrError> {
+    for (layer, circuit_layer) in circuit.layers_top_down().enumerate() {
+        let requested = circuit_layer.selector_size();
+
+        if requested > config.max_selector_size {
+            return Err(GkrError::SelectorTooLarge { layer, requested });
+        }
+
+        if needs_sum_of_products(&circuit_layer)
+            && config.fbc_construction != FbcConstruction::SumOfProducts
+        {
+            return Err(GkrError::UnsupportedConstruction { layer });
+        }
+    }
+
+    config
+        .padding
+        .apply(inputs, circuit.input_width())
+        .map_err(|err| GkrError::InputLengthMismatch {
+            expected: err.expected,
+            actual: err.actual,
+        })
+}
+
+/// Verifies against an ongoing transcript so the GKR challenges chain with an
+/// outer protocol. The transcript must be in the same state the prover's was
+/// in when `prove_with_transcript` was called.
+pub fn verify_in_transcript<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+    transcript: &mut Transcript<F, impl Write>,
+) -> Result<(), GkrError> {
+    verify_interactive(proof, circuit, inputs, transcript)
+}
+
+/// Verifies like `verify_in_transcript` without the inputs, taking
+/// `input_claims` as the input poly's evaluations at the bottom layer's
+/// `r_b` and `r_c`. Nothing here ties the claims to any inputs: the caller
+/// must check them at `VerifiedChallenges::input_points`, e.g. by opening a
+/// commitment to the inputs.
+pub fn verify_with_input_claims<F: PrimeField>(
+    proof: &Proof<F>,
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    input_claims: (F, F),
+    transcript: &mut Transcript<F, impl Write>,
+) -> Result<VerifiedChallenges<F>, GkrError> {
+    verify_layers(
+        proof,
+        circuit,
+        InputSource::Claims(input_claims.0, input_claims.1),
+        transcript,
+        FbcConstruction::default(),
+        SelectorEvaluation::default(),
+        &mut |_| {},
+    )
+}
+
+/// Verifies `proof` and then draws one more challenge from the verifier's
+/// transcript, for protocols building on the accepted proof. The inputs are
+/// absorbed first, since the rest of the transcript only holds proof data.
+pub fn verify_and_squeeze<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+) -> Result<F, GkrError> {
+    let mut transcript = Transcript::<F>::new();
+    verify_in_transcript(proof, circuit, inputs, &mut transcript)?;
+
+    transcript.append_fields(inputs);
+
+    Ok(transcript.get_random_challenge())
+}
+
+pub fn verify_with_nonce<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+    nonce: &[u8],
+) -> Result<(), GkrError> {
+    verify_in_transcript(proof, circuit, inputs, &mut nonce_transcript(nonce))
+}
+
+pub fn verify_deterministic<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+    seed: &[u8],
+) -> Result<(), GkrError> {
+    let mut transcript = Transcript::new();
+    transcript.append(seed);
+
+    verify_in_transcript(proof, circuit, inputs, &mut transcript)
+}
+
+/// Verifies with challenges drawn from `challenges`, which must hand out the
+/// same sequence the prover's source did.
+pub fn verify_interactive<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+    challenges: &mut impl ChallengeSource<F>,
+) -> Result<(), GkrError> {
+    verify_layers(
+        &proof,
+        &circuit,
+        InputSource::Inputs(inputs),
+        challenges,
+        FbcConstruction::default(),
+        SelectorEvaluation::default(),
+        &mut |_| {},
+    )
+    .map(|_| ())
+}
+
+/// Verifies like `verify`, but checks the layers in parallel. Each layer's
+/// claim is chained from the layer above, so all challenges and claims are
+/// first derived from the proof in one sequential pass; after that every
+/// layer's sum-check rounds and final claim can be checked on its own.
+/// Without the `parallel` feature the layers are checked one by one.
+pub fn verify_parallel<F: PrimeField>(
+    proof: &Proof<F>,
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+) -> Result<(), GkrError> {
+    let derived = derive_layer_claims(proof, circuit, inputs)?;
+    let layers: Vec<Layer<F>> = circuit.layers_top_down().collect();
+
+    #[cfg(feature = "parallel")]
+    let layers = layers.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let layers = layers.iter();
+
+    let results: Vec<Result<(), GkrError>> = layers
+        .enumerate()
+        .map(|(i, layer)| check_derived_layer(proof, inputs, &derived, i, layer))
+        .collect();
+
+    results.into_iter().collect()
+}
+
+/// Checks the layers one by one from challenges and claims derived up front
+/// like `verify_parallel`, so with `VerifyMode::CheckAll` the layers below a
+/// failing one are still checked against the claims the proof makes for
+/// them. Errors that are not about one layer's arithmetic, a wrong input
+/// length or a malformed proof, are returned instead of a report.
+pub fn verify_with_mode<F: PrimeField>(
+    proof: &Proof<F>,
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+    mode: VerifyMode,
+) -> Result<VerifyReport, GkrError> {
+    let derived = derive_layer_claims(proof, circuit, inputs)?;
+    let mut layers = Vec::with_capacity(circuit.depth());
+
+    for (i, layer) in circuit.layers_top_down().enumerate() {
+        let result = check_derived_layer(proof, inputs, &derived, i, &layer);
+        let failed = result.is_err();
+        layers.push(result);
+
+        if failed && mode == VerifyMode::FailFast {
+            break;
+        }
+    }
+
+    Ok(VerifyReport { layers })
+}
+
+/// Every layer's challenges, starting claim, claim fold and sent skip
+/// evaluation, replayed from the proof without checking any layer.
+struct DerivedClaims<F: PrimeField> {
+    output_challenge: F,
+    challenges: Vec<LayerChallenges<F>>,
+    claims: Vec<F>,
+    folds: Vec<Option<ClaimFold<F>>>,
+    skip_evaluations: Vec<Option<F>>,
+}
+
+fn derive_layer_claims<F: PrimeField>(
+    proof: &Proof<F>,
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+) -> Result<DerivedClaims<F>, GkrError> {
+    if inputs.len() != circuit.input_width() {
+        return Err(GkrError::InputLengthMismatch {
+            expected: circuit.input_width(),
+            actual: inputs.len(),
+        });
+    }
+
+    let shape = ProofShape::of(circuit, FbcConstruction::default());
+    shape.check(proof)?;
+    shape.check_canonical(proof)?;
+
+    let num_layers = circuit.depth();
+    let (output_claim, output_challenge) = proof.output_claim();
+    let challenges = proof.derive_challenges(circuit)?;
+
+    let mut claims = Vec::with_capacity(num_layers);
+    let mut folds = Vec::with_capacity(num_layers);
+    let mut skip_evaluations = Vec::with_capacity(num_layers);
+    let mut sent_skip_evaluations = proof.skip_evaluations.iter();
+    let mut pending_skip: Option<SkipClaim<F>> = None;
+    claims.push(output_claim);
+    folds.push(None);
+
+    for (i, (layer, layer_challenges)) in circuit.layers_top_down().zip(&challenges).enumerate() {
+        let skip_evaluation = layer_challenges
+            .skip_weight
+            .map(|_| *sent_skip_evaluations.next().unwrap());
+        skip_evaluations.push(skip_evaluation);
+
+        if i == num_layers - 1 {
+            break;
+        }
+
+        let (alpha, beta) = layer_challenges
+            .alpha_beta
+            .expect("every layer above the inputs has an alpha/beta pair");
+        check_challenge_split(&layer, i, &layer_challenges.sum_check)?;
+        let (r_b, r_c) = claim_points(&layer, &layer_challenges.sum_check);
+
+        let fold = ClaimFold {
+            r_b: r_b.to_vec(),
+            r_c: r_c.to_vec(),
+            alpha,
+            beta,
+            skip: pending_skip.take(),
+        };
+
+        claims.push(fold.claim(proof.claimed_evaluations[i]));
+        folds.push(Some(fold));
+        pending_skip =
+            layer_challenges
+                .skip_weight
+                .zip(skip_evaluation)
+                .map(|(gamma, evaluation)| SkipClaim {
+                    gamma,
+                    point: skip_point(&layer_challenges.sum_check).to_vec(),
+                    evaluation,
+                });
+    }
+
+    Ok(DerivedClaims {
+        output_challenge,
+        challenges,
+        claims,
+        folds,
+        skip_evaluations,
+    })
+}
+
+/// Checks the sum-check and final claim of the layer at top-down index `i`
+/// against `derived`.
+fn check_derived_layer<F: PrimeField>(
+    proof: &Proof<F>,
+    inputs: &[F],
+    derived: &DerivedClaims<F>,
+    i: usize,
+    layer: &Layer<F>,
+) -> Result<(), GkrError> {
+    let num_layers = derived.claims.len();
+
+    let sum_check_verify = gkr_verify(
+        proof.proof_polynomials[i].clone(),
+        derived.claims[i],
+        layer.round_degree(),
+        &mut FixedChallenges::new(derived.challenges[i].sum_check.clone()),
+        |challenges| {
+            let evaluations = if i == num_layers - 1 {
+                evaluate_input_poly(inputs, challenges)
+            } else {
+                proof.claimed_evaluations[i]
+            };
+            let o_3 = get_skip_evaluation(layer, derived.skip_evaluations[i], inputs, challenges);
+
+            get_expected_claim(
+                layer,
+                FbcConstruction::default(),
+                SelectorEvaluation::default(),
+                derived.folds[i].as_ref(),
+                derived.output_challenge,
+                challenges,
+                get_wire_evaluations(layer, challenges, evaluations, o_3),
+            )
+        },
+    );
+
+    if sum_check_verify.oracle_evaluation.is_none() {
+        return Err(GkrError::SumCheckFailed { layer: i });
+    }
+
+    if !sum_check_verify.verified {
+        return Err(GkrError::ClaimMismatch { layer: i });
+    }
+
+    Ok(())
+}
+
+pub fn verify_with_challenges<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+) -> Result<VerifiedChallenges<F>, GkrError> {
+    let mut transcript = Transcript::<F>::new();
+
+    verify_layers(
+        &proof,
+        &circuit,
+        InputSource::Inputs(inputs),
+        &mut transcript,
+        FbcConstruction::default(),
+        SelectorEvaluation::default(),
+        &mut |_| {},
+    )
+}
+
+/// One layer's final claim check: the sum its sum-check started from, the
+/// claim recomputed from selectors and w-evaluations, and the sum-check's
+/// final claim it is compared against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaimCheck<F: PrimeField> {
+    pub layer: usize,
+    pub claimed_sum: F,
+    pub expected_claim: F,
+    pub final_claimed_sum: F,
+}
+
+/// Every claim check `verify` made, in order, and how verification ended.
+/// A failed sum-check ends the trail before that layer's claim check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditTrail<F: PrimeField> {
+    pub checks: Vec<ClaimCheck<F>>,
+    pub result: Result<(), GkrError>,
+}
+
+pub fn verify_audit<F: PrimeField>(
+    proof: &Proof<F>,
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+) -> AuditTrail<F> {
+    let mut checks = Vec::with_capacity(circuit.depth());
+
+    let result = verify_layers(
+        proof,
+        circuit,
+        InputSource::Inputs(inputs),
+        &mut Transcript::new(),
+        FbcConstruction::default(),
+        SelectorEvaluation::default(),
+        &mut |check| checks.push(check.clone()),
+    )
+    .map(|_| ());
+
+    AuditTrail { checks, result }
+}
+
+/// Why a `verify_verbose` run failed; `claim_difference` is
+/// `expected_claim - final_claimed_sum` when a layer's final claim check fails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyFailure<F: PrimeField> {
+    pub error: GkrError,
+    pub claim_difference: Option<F>,
+}
+
+/// Like `verify`, but logs the failing layer and how far its claim is off.
+pub fn verify_verbose<F: PrimeField>(
+    proof: &Proof<F>,
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+) -> Result<(), VerifyFailure<F>> {
+    let mut claim_difference = None;
+
+    verify_layers(
+        proof,
+        circuit,
+        InputSource::Inputs(inputs),
+        &mut Transcript::new(),
+        FbcConstruction::default(),
+        SelectorEvaluation::default(),
+        &mut |check| {
+            if check.expected_claim != check.final_claimed_sum {
+                let (layer, difference) =
+                    (check.layer, check.expected_claim - check.final_claimed_sum);
+
+                log::warn!(
+                    "claim mismatch at layer {layer}: claim - final_claimed_sum = {difference}"
+                );
+                claim_difference = Some(difference);
+            }
+        },
+    )
+    .map(|_| ())
+    .map_err(|error| {
+        log::warn!("verification failed: {error:?}");
+
+        VerifyFailure {
+            error,
+            claim_difference,
+        }
+    })
+}
+
+/// Verifies the proof and, when `output_claims` is given, that the output
+/// poly takes value `output_claims[i]` at the boolean point of output `i`.
+/// `m_0` is derived from the output poly, so this binds the claimed outputs
+/// to what the sum-checks were run against.
+pub fn verify_with_output_claims<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+    output_claims: Option<&[F]>,
+) -> Result<(), GkrError> {
+    if let Some(output_claims) = output_claims {
+        let output_width = circuit.layer(circuit.depth() - 1).gates.len();
+
+        if output_claims.len() != output_width {
+            return Err(GkrError::OutputClaimMismatch {
+                index: output_claims.len().min(output_width),
+            });
+        }
+
+        let num_vars = proof.output_poly.num_of_vars;
+
+        for (index, claim) in output_claims.iter().enumerate() {
+            let boolean_point = (0..num_vars)
+                .map(|bit| F::from(((index >> (num_vars - 1 - bit)) & 1) as u64))
+                .collect();
+
+            if proof.output_poly.evaluate(boolean_point) != *claim {
+                return Err(GkrError::OutputClaimMismatch { index });
+            }
+        }
+    }
+
+    verify_in_transcript(proof, circuit, inputs, &mut Transcript::new())
+}
+
+/// Proves with `expected_output` absorbed first, so the proof only verifies
+/// against that same assertion.
+pub fn prove_output_assertion<F: PrimeField>(
+    circuit: &mut Circuit<F>,
+    inputs: &[F],
+    expected_output: &[F],
+) -> Proof<F> {
+    let mut transcript = Transcript::new();
+    transcript.append_fields(expected_output);
+
+    prove_with_transcript(circuit, inputs, &mut transcript)
+}
+
+/// Rejects unless the output bound by the output poly is `expected_output`
+/// and the proof was made against that assertion.
+pub fn verify_output_assertion<F: PrimeField>(
+    proof: Proof<F>,
+    circuit: Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+    expected_output: &[F],
+) -> Result<(), GkrError> {
+    let output_width = circuit.layer(circuit.depth() - 1).gates.len();
+
+    if expected_output.len() != output_width {
+        return Err(GkrError::OutputClaimMismatch {
+            index: expected_output.len().min(output_width),
+        });
+    }
+
+    let asserted_poly = output_layer_poly(expected_output.to_vec());
+    if let Some((index, _, _)) = proof.output_poly.first_difference(&asserted_poly) {
+        return Err(GkrError::OutputClaimMismatch { index });
+    }
+
+    let mut transcript = Transcript::new();
+    transcript.append_fields(expected_output);
+
+    verify_in_transcript(proof, circuit, inputs, &mut transcript)
+}
+
+/// Index of the first layer (top-down) whose sum-check or final claim check
+/// fails, or `None` if the proof verifies.
+pub fn find_first_divergence<F: PrimeField>(
+    proof: &Proof<F>,
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+) -> Option<usize> {
+    let mut transcript = Transcript::<F>::new();
+
+    match verify_layers(
+        proof,
+        circuit,
+        InputSource::Inputs(inputs),
+        &mut transcript,
+        FbcConstruction::default(),
+        SelectorEvaluation::default(),
+        &mut |_| {},
+    ) {
+        Ok(_) => None,
+        Err(GkrError::SumCheckFailed { layer })
+        | Err(GkrError::MalformedProof { layer })
+        | Err(GkrError::ClaimMismatch { layer })
+        | Err(GkrError::SelectorTooLarge { layer, .. })
+        | Err(GkrError::SelectorOpeningFailed { layer })
+        | Err(GkrError::NonCanonicalElement { layer })
+        | Err(GkrError::ChallengeSplitMismatch { layer, .. })
+        | Err(GkrError::UnsupportedConstruction { layer })
+        | Err(GkrError::InputsNotAvailable { layer }) => Some(layer),
+        Err(GkrError::InputLengthMismatch { .. }) => Some(circuit.depth() - 1),
+        Err(GkrError::OutputClaimMismatch { .. }) => Some(0),
+        Err(GkrError::InvalidSplit { .. })
+        | Err(GkrError::ProofCountMismatch { .. })
+        | Err(GkrError::InputClaimMismatch) => None,
+    }
+}
+
+/// Where the verifier gets the input poly's evaluations at the bottom
+/// layer's `r_b` and `r_c`: from the inputs, or from claims about them that
+/// an outer protocol checks, e.g. against a commitment.
+enum InputSource<'a, F: PrimeField> {
+    Inputs(&'a [F]),
+    Claims(F, F),
+}
+
+fn verify_layers<F: PrimeField>(
+    proof: &Proof<F>,
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    input_source: InputSource<F>,
+    transcript: &mut impl ChallengeSource<F>,
+    construction: FbcConstruction,
+    selector_evaluation: SelectorEvaluation,
+    on_claim_check: &mut dyn FnMut(&ClaimCheck<F>),
+) -> Result<VerifiedChallenges<F>, GkrError> {
+    let inputs = match input_source {
+        InputSource::Inputs(inputs) if inputs.len() != circuit.input_width() => {
+            return Err(GkrError::InputLengthMismatch {
+                expected: circuit.input_width(),
+                actual: inputs.len(),
+            });
+        }
+        InputSource::Inputs(inputs) => inputs,
+        InputSource::Claims(..) => &[],
+    };
+
+    let shape = ProofShape::of(circuit, construction);
+    shape.check(proof)?;
+    shape.check_canonical(proof)?;
+
+    let mut stream = ChallengeStream::new(transcript);
+    let (mut current_claim, init_random_challenge) = stream.output_claim(&proof.output_poly);
+
+    let mut fold: Option<ClaimFold<F>> = None;
+    let mut pending_skip: Option<SkipClaim<F>> = None;
+    let mut sent_skip_evaluations = proof.skip_evaluations.iter();
+
+    let num_layers = circuit.depth();
+    let mut challenges = VerifiedChallenges {
+        output_challenge: init_random_challenge,
+        layer_challenges: Vec::with_capacity(num_layers),
+        alphas_betas: Vec::with_capacity(num_layers.saturating_sub(1)),
+        skip_weights: Vec::new(),
+    };
+
+    for (i, layer) in circuit.layers_top_down().enumerate() {
+        stream.start_layer(i);
+
+        let num_rounds = layer.num_sumcheck_rounds() + construction.extra_rounds();
+
+        let round_polys = &proof.proof_polynomials[i];
+        let skip_evaluation = sends_skip_evaluation(&layer, i, num_layers)
+            .then(|| *sent_skip_evaluations.next().unwrap());
+
+        if needs_sum_of_products(&layer) && construction != FbcConstruction::SumOfProducts {
+            return Err(GkrError::UnsupportedConstruction { layer: i });
+        }
+
+        if matches!(input_source, InputSource::Claims(..))
+            && layer.has_skip_connections()
+            && skip_evaluation.is_none()
+        {
+            return Err(GkrError::InputsNotAvailable { layer: i });
+        }
+
+        let sum_check_verify = gkr_verify(
+            round_polys.clone(),
+            current_claim,
+            layer.round_degree(),
+            &mut stream,
+            |challenges| {
+                let bc_challenges = &challenges[construction.extra_rounds()..];
+                let evaluations = match (i == num_layers - 1, &input_source) {
+                    (true, InputSource::Inputs(inputs)) => {
+                        evaluate_input_poly(inputs, bc_challenges)
+                    }
+                    (true, InputSource::Claims(o_1, o_2)) => (*o_1, *o_2),
+                    (false, _) => proof.claimed_evaluations[i],
+                };
+                let o_3 = get_skip_evaluation(&layer, skip_evaluation, inputs, bc_challenges);
+
+                get_expected_claim(
+                    &layer,
+                    construction,
+                    selector_evaluation,
+                    fold.as_ref(),
+                    init_random_challenge,
+                    challenges,
+                    get_wire_evaluations(&layer, bc_challenges, evaluations, o_3),
+                )
+            },
+        );
+
+        let expected_claim = match sum_check_verify.oracle_evaluation {
+            Some(expected_claim) if sum_check_verify.random_challenges.len() == num_rounds => {
+                expected_claim
+            }
+            _ => return Err(GkrError::SumCheckFailed { layer: i }),
+        };
+
+        on_claim_check(&ClaimCheck {
+            layer: i,
+            claimed_sum: current_claim,
+            expected_claim,
+            final_claimed_sum: sum_check_verify.final_claimed_sum,
+        });
+
+        if !sum_check_verify.verified {
+            return Err(GkrError::ClaimMismatch { layer: i });
+        }
+
+        let current_random_challenge = sum_check_verify.random_challenges;
+        let bc_challenges = &current_random_challenge[construction.extra_rounds()..];
+
+        if i < num_layers - 1 {
+            check_challenge_split(&layer, i, bc_challenges)?;
+
+            let (o_1, o_2) = proof.claimed_evaluations[i];
+            let (r_b, r_c) = claim_points(&layer, bc_challenges);
+
+            let (alpha, beta) = stream.reduce(o_1, o_2);
+
+            let skip = skip_evaluation.map(|o_3| SkipClaim {
+                gamma: stream.skip_weight(o_3),
+                point: skip_point(bc_challenges).to_vec(),
+                evaluation: o_3,
+            });
+
+            let next_fold = ClaimFold {
+                r_b: r_b.to_vec(),
+                r_c: r_c.to_vec(),
+                alpha,
+                beta,
+                skip: pending_skip.take(),
+            };
+
+            current_claim = next_fold.claim((o_1, o_2));
+            challenges.alphas_betas.push((alpha, beta));
+            if let Some(skip) = &skip {
+                challenges.skip_weights.push(skip.gamma);
+            }
+            fold = Some(next_fold);
+            pending_skip = skip;
+        }
+
+        challenges.layer_challenges.push(current_random_challenge);
+    }
+
+    Ok(challenges)
+}
+
+/// What a well-formed proof looks like: the number of variables of its
+/// output poly, the number of round polys and round poly length of every
+/// layer top-down, and the top-down indices of the layers sending a skip
+/// evaluation. `verify_preprocessed` builds one from a verifier key.
+pub(crate) struct ProofShape {
+    pub(crate) output_vars: usize,
+    pub(crate) layers: Vec<(usize, usize)>,
+    pub(crate) skip_layers: Vec<usize>,
+}
+
+impl ProofShape {
+    pub(crate) fn of<F: PrimeField>(
+        circuit: &Circuit<F, impl LayerSource<F>>,
+        construction: FbcConstruction,
+    ) -> Self {
+        let num_layers = circuit.depth();
+        let output_vars = match num_layers {
+            0 => 0,
+            _ => (circuit.layer(num_layers - 1).gates.len().ilog2() as usize).max(1),
+        };
+
+        let layers = circuit
+            .layers_top_down()
+            .map(|layer| {
+                (
+                    layer.num_sumcheck_rounds() + construction.extra_rounds(),
+                    layer.round_degree() + 1,
+                )
+            })
+            .collect();
+
+        let skip_layers = circuit
+            .layers_top_down()
+            .enumerate()
+            .filter(|(i, layer)| sends_skip_evaluation(layer, *i, num_layers))
+            .map(|(i, _)| i)
+            .collect();
+
+        Self {
+            output_vars,
+            layers,
+            skip_layers,
+        }
+    }
+
+    /// Checks that `proof` has an output poly with `output_vars` variables
+    /// and one entry per layer, each with as many round polys as the layer's
+    /// sum-check has rounds and each round poly as long as the layer's, a
+    /// claimed evaluation pair for every layer above the inputs and a skip
+    /// evaluation for every layer sending one. A proof never fits a shape
+    /// without layers.
+    pub(crate) fn check<F: PrimeField>(&self, proof: &Proof<F>) -> Result<(), GkrError> {
+        let num_layers = self.layers.len();
+
+        if num_layers == 0
+            || proof.output_poly.num_of_vars != self.output_vars
+            || proof.output_poly.evaluation.len() != 1 << self.output_vars
+        {
+            return Err(GkrError::MalformedProof { layer: 0 });
+        }
+
+        for (i, (num_rounds, round_poly_len)) in self.layers.iter().enumerate() {
+            let well_formed = match proof.proof_polynomials.get(i) {
+                Some(round_polys) => {
+                    round_polys.len() == *num_rounds
+                        && round_polys
+                            .iter()
+                            .all(|round_poly| round_poly.len() == *round_poly_len)
+                }
+                None => false,
+            };
+
+            if !well_formed {
+                return Err(GkrError::MalformedProof { layer: i });
+            }
+        }
+
+        if proof.proof_polynomials.len() != num_layers {
+            return Err(GkrError::MalformedProof { layer: num_layers });
+        }
+
+        if proof.claimed_evaluations.len() != num_layers - 1 {
+            return Err(GkrError::MalformedProof {
+                layer: proof.claimed_evaluations.len().min(num_layers - 1),
+            });
+        }
+
+        if proof.skip_evaluations.len() != self.skip_layers.len() {
+            return Err(GkrError::MalformedProof {
+                layer: self
+                    .skip_layers
+                    .get(proof.skip_evaluations.len())
+                    .copied()
+                    .unwrap_or(num_layers),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that every field element of `proof` is reduced below the
+    /// modulus, before any arithmetic on them. An element built without
+    /// reduction, e.g. by `Fp::new_unchecked`, still converts to a canonical
+    /// bigint, so it is caught by rebuilding it from that bigint and
+    /// comparing representations. The proof must already have passed
+    /// `check`.
+    pub(crate) fn check_canonical<F: PrimeField>(&self, proof: &Proof<F>) -> Result<(), GkrError> {
+        let canonical = |xs: &[F]| {
+            xs.iter()
+                .all(|x| F::from_bigint(x.into_bigint()) == Some(*x))
+        };
+
+        if !canonical(&proof.output_poly.evaluation) {
+            return Err(GkrError::NonCanonicalElement { layer: 0 });
+        }
+
+        for (i, round_polys) in proof.proof_polynomials.iter().enumerate() {
+            let claims = match proof.claimed_evaluations.get(i) {
+                Some((o_1, o_2)) => vec![*o_1, *o_2],
+                None => Vec::new(),
+            };
+
+            if !round_polys.iter().all(|round_poly| canonical(round_poly)) || !canonical(&claims) {
+                return Err(GkrError::NonCanonicalElement { layer: i });
+            }
+        }
+
+        for (layer, o_3) in self.skip_layers.iter().zip(&proof.skip_evaluations) {
+            if !canonical(&[*o_3]) {
+                return Err(GkrError::NonCanonicalElement { layer: *layer });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How the two claims about the layer above were reduced to one: the points
+/// `r_b`, `r_c` they were made at and the challenges weighting them, plus
+/// the claim of any skip connection two layers up. The output layer has no
+/// claims to fold, so it is the only layer without one and uses the output
+/// challenge instead; no placeholder alpha/beta exists to be read by mistake.
+struct ClaimFold<F: PrimeField> {
+    r_b: Vec<F>,
+    r_c: Vec<F>,
+    alpha: F,
+    beta: F,
+    skip: Option<SkipClaim<F>>,
+}
+
+/// A skip connection's claim `evaluation` about the layer two below at
+/// `point`, folded into that layer's claims with weight `gamma`.
+struct SkipClaim<F: PrimeField> {
+    gamma: F,
+    point: Vec<F>,
+    evaluation: F,
+}
+
+impl<F: PrimeField> ClaimFold<F> {
+    /// The folded claim `alpha * o_1 + beta * o_2 + gamma * o_3`.
+    fn claim(&self, (o_1, o_2): (F, F)) -> F {
+        let skip_claim = match &self.skip {
+            Some(skip) => skip.gamma * skip.evaluation,
+            None => F::zero(),
+        };
+
+        (self.alpha * o_1) + (self.beta * o_2) + skip_claim
+    }
+
+    /// `get_folded_selector_weights` plus `gamma * eq(point, a)` for a skip
+    /// connection claim.
+    fn weights(&self) -> Vec<F> {
+        let mut weights = get_folded_selector_weights(&self.r_b, &self.r_c, self.alpha, self.beta);
+
+        if let Some(skip) = &self.skip {
+            for (weight, eq) in weights.iter_mut().zip(eq_table(&skip.point)) {
+                *weight += skip.gamma * eq;
+            }
+        }
+
+        weights
+    }
+}
+
+/// Whether `layer` has lookup or custom gates, whose terms only the
+/// sum-of-products fbc poly has.
+pub(crate) fn needs_sum_of_products<F: PrimeField>(layer: &Layer<F>) -> bool {
+    #[cfg(feature = "lookup")]
+    if layer.has_lookups() {
+        return true;
+    }
+
+    layer.has_custom_gates()
+}
+
+/// Whether the layer at top-down index `i` sends a claim about the layer two
+/// below for its skip connections. When that layer is the inputs, the
+/// verifier evaluates them itself.
+fn sends_skip_evaluation<F: PrimeField>(layer: &Layer<F>, i: usize, num_layers: usize) -> bool {
+    layer.has_skip_connections() && i + 2 < num_layers
+}
+
+/// The points `(r_b, r_c)` the claims about the layer below are made at,
+/// without the two leading bits that pick the part of the wire tables of
+/// `Layer::wire_tables` a layer with skip connections reads.
+fn claim_points<'a, F: PrimeField>(layer: &Layer<F>, bc_challenges: &'a [F]) -> (&'a [F], &'a [F]) {
+    let (r_b, r_c) = bc_challenges.split_at(bc_challenges.len() / 2);
+    debug_assert!(
+        r_b.len() == r_c.len() && r_b.len() == layer.selector_widths().1 as usize,
+        "{} b and c challenges do not split into two input wires of {} bits",
+        bc_challenges.len(),
+        layer.selector_widths().1
+    );
+
+    match layer.has_skip_connections() {
+        true => (&r_b[2..], &r_c[2..]),
+        false => (r_b, r_c),
+    }
+}
+
+/// Checks the b and c challenges of the layer at top-down index `i` split
+/// evenly into `r_b` and `r_c` of the layer's input-wire width, as
+/// `claim_points` assumes.
+fn check_challenge_split<F: PrimeField>(
+    layer: &Layer<F>,
+    i: usize,
+    bc_challenges: &[F],
+) -> Result<(), GkrError> {
+    let expected = 2 * layer.selector_widths().1 as usize;
+
+    match bc_challenges.len() == expected {
+        true => Ok(()),
+        false => Err(GkrError::ChallengeSplitMismatch {
+            layer: i,
+            expected,
+            actual: bc_challenges.len(),
+        }),
+    }
+}
+
+/// The point the skip connection claim about the layer two below is made
+/// at: `r_c` without its leading bit.
+fn skip_point<F: PrimeField>(bc_challenges: &[F]) -> &[F] {
+    &bc_challenges[bc_challenges.len() / 2 + 1..]
+}
+
+/// The layer two below at `skip_point` for a layer with skip connections:
+/// the evaluation sent in the proof, or the inputs evaluated directly.
+fn get_skip_evaluation<F: PrimeField>(
+    layer: &Layer<F>,
+    sent: Option<F>,
+    inputs: &[F],
+    bc_challenges: &[F],
+) -> Option<F> {
+    match (layer.has_skip_connections(), sent) {
+        (false, _) => None,
+        (true, Some(o_3)) => Some(o_3),
+        (true, None) => {
+            Some(MultilinearPoly::new(inputs.to_vec()).evaluate(skip_point(bc_challenges).to_vec()))
+        }
+    }
+}
+
+/// The wire tables of `Layer::wire_tables` at `(r_b, r_c)`, lifted from the
+/// claims `(o_1, o_2)` about the layer below and `o_3` about the layer two
+/// below. Without skip connections they are the claims themselves.
+fn get_wire_evaluations<F: PrimeField>(
+    layer: &Layer<F>,
+    bc_challenges: &[F],
+    (o_1, o_2): (F, F),
+    o_3: Option<F>,
+) -> (F, F) {
+    let o_3 = match (layer.has_skip_connections(), o_3) {
+        (true, Some(o_3)) => o_3,
+        _ => return (o_1, o_2),
+    };
+
+    let (r_b, r_c) = bc_challenges.split_at(bc_challenges.len() / 2);
+    let below = |r: &[F]| (F::one() - r[0]) * (F::one() - r[1]);
+
+    (below(r_b) * o_1, (below(r_c) * o_2) + (r_c[0] * o_3))
+}
+
+/// The claim a layer's last sum-check round must match, from its selectors
+/// at `challenges` and the wire evaluations `(o_1, o_2)` of the layer below.
+/// Lookup and custom gate layers must use the sum-of-products construction,
+/// which callers check before running the layer's sum-check.
+fn get_expected_claim<F: PrimeField>(
+    layer: &Layer<F>,
+    construction: FbcConstruction,
+    selector_evaluation: SelectorEvaluation,
+    fold: Option<&ClaimFold<F>>,
+    output_challenge: F,
+    challenges: &[F],
+    (o_1, o_2): (F, F),
+) -> F {
+    let (selector_challenge, bc_challenges) = challenges.split_at(construction.extra_rounds());
+
+    let (a_r, m_r) = match (selector_evaluation, fold) {
+        (SelectorEvaluation::Table, None) => {
+            get_verifier_selector_evals(layer, output_challenge, bc_challenges)
+        }
+        (SelectorEvaluation::Table, Some(fold)) => {
+            get_folded_verifier_selector_evals(layer, bc_challenges, fold)
+        }
+        (SelectorEvaluation::Direct, _) => get_direct_selector_evals(
+            layer,
+            &output_points(fold, &output_challenge),
+            bc_challenges,
+        ),
+    };
+
+    let expected_claim =
+        combine_verifier_claim(selector_challenge.first().copied(), a_r, m_r, o_1, o_2);
+
+    #[cfg(feature = "lookup")]
+    let expected_claim = match (layer.has_lookups(), construction) {
+        (false, _) => expected_claim,
+        (true, FbcConstruction::SumOfProducts) => {
+            let [k_r, l_r, r_r] = get_lookup_selector_evals(
+                layer,
+                &output_points(fold, &output_challenge),
+                bc_challenges,
+            );
+
+            expected_claim + k_r + (l_r * o_1) + (r_r * o_2)
+        }
+        (true, FbcConstruction::SingleProduct) => {
+            panic!("lookup gates need the sum-of-products fbc poly")
+        }
+    };
+
+    match (layer.has_custom_gates(), construction) {
+        (false, _) => expected_claim,
+        (true, FbcConstruction::SumOfProducts) => {
+            expected_claim
+                + get_custom_claim(
+                    layer,
+                    &output_points(fold, &output_challenge),
+                    bc_challenges,
+                    (o_1, o_2),
+                )
+        }
+        (true, FbcConstruction::SingleProduct) => {
+            panic!("custom gates need the sum-of-products fbc poly")
+        }
+    }
+}
+
+/// Trimmed coefficients of the round poly with evaluations `g(0), g(1), ...`.
+fn round_poly_coefficients<F: PrimeField>(evaluations: &[F]) -> Vec<F> {
+    let points = evaluations
+        .iter()
+        .enumerate()
+        .map(|(i, y)| (F::from(i as u64), *y))
+        .collect();
+
+    UnivariatePoly::interpolate(points).coefficient
+}
+
+fn initiate_protocol<F: PrimeField>(
+    transcript: &mut impl ChallengeSource<F>,
+    output_poly: &MultilinearPoly<F>,
+) -> (F, F) {
+    ChallengeStream::new(transcript).output_claim(output_poly)
+}
+
+pub fn get_fbc_poly<F: PrimeField>(
+    random_challenge: F,
+    layer: Layer<F>,
+    w_b: &[F],
+    w_c: &[F],
+) -> SumPoly<F> {
+    let (add_i, mul_i) = get_output_selectors(&layer, random_challenge);
+
+    let summed_w_poly = tensor_add_mul_polynomials(w_b, w_c, Operation::Add);
+    let multiplied_w_poly = tensor_add_mul_polynomials(w_b, w_c, Operation::Mul);
+
+    let add_eval_product = ProductPoly::new(vec![add_i.evaluation, summed_w_poly.evaluation]);
+    let mul_eval_product = ProductPoly::new(vec![mul_i.evaluation, multiplied_w_poly.evaluation]);
+
+    SumPoly::new(vec![add_eval_product, mul_eval_product])
+}
+
+/// Merges the add and mul terms into one product over an extra leading
+/// variable `s`: `sel(s, b, c) * w(s, b, c)`, where `s = 0` picks
+/// `add_i * (w_b + w_c)` and `s = 1` picks `mul_i * (w_b * w_c)`.
+pub fn get_fbc_poly_single_product<F: PrimeField>(
+    add_i: MultilinearPoly<F>,
+    mul_i: MultilinearPoly<F>,
+    w_b: &[F],
+    w_c: &[F],
+) -> ProductPoly<F> {
+    let summed_w_poly = tensor_add_mul_polynomials(w_b, w_c, Operation::Add);
+    let multiplied_w_poly = tensor_add_mul_polynomials(w_b, w_c, Operation::Mul);
+
+    let mut selector = add_i.evaluation;
+    selector.extend(mul_i.evaluation);
+
+    let mut w_poly = summed_w_poly.evaluation;
+    w_poly.extend(multiplied_w_poly.evaluation);
+
+    ProductPoly::new(vec![selector, w_poly])
+}
+
+fn get_output_selectors<F: PrimeField>(
+    layer: &Layer<F>,
+    random_challenge: F,
+) -> (MultilinearPoly<F>, MultilinearPoly<F>) {
+    let (add_i, mul_i) = layer.get_fbc_selectors();
+
+    (
+        add_i.partial_evaluate(0, &random_challenge),
+        mul_i.partial_evaluate(0, &random_challenge),
+    )
+}
+
+/// The fbc poly with the output-gate variables of its selectors folded by
+/// `weights`, see `get_folded_selector_weights`.
+fn get_folded_fbc_poly<F: PrimeField>(
+    layer: &Layer<F>,
+    w_b: &[F],
+    w_c: &[F],
+    weights: &[F],
+) -> SumPoly<F> {
+    let (summed_add_i, summed_mul_i) = fold_fbc_selectors(layer, weights);
+
+    let summed_w_poly = tensor_add_mul_polynomials(w_b, w_c, Operation::Add);
+    let multiplied_w_poly = tensor_add_mul_polynomials(w_b, w_c, Operation::Mul);
+
+    let add_product_poly =
+        ProductPoly::new(vec![summed_add_i.evaluation, summed_w_poly.evaluation]);
+    let mul_product_poly =
+        ProductPoly::new(vec![summed_mul_i.evaluation, multiplied_w_poly.evaluation]);
+
+    SumPoly::new(vec![add_product_poly, mul_product_poly])
+}
+
+/// Folds the output-gate variables of a layer's add/mul selectors into
+/// `alpha * sel(r_b, b, c) + beta * sel(r_c, b, c)`. The folding weights only
+/// depend on the challenges, so they are computed once and shared by both
+/// selectors.
+pub fn get_folded_selectors<F: PrimeField>(
+    layer: &Layer<F>,
+    r_b: &[F],
+    r_c: &[F],
+    alpha: F,
+    beta: F,
+) -> (MultilinearPoly<F>, MultilinearPoly<F>) {
+    let weights = get_folded_selector_weights(r_b, r_c, alpha, beta);
+
+    fold_fbc_selectors(layer, &weights)
+}
+
+/// The claim the input layer's sum-check must end on, derived from the
+/// prover's folded fbc poly rather than the verifier's selector evaluation:
+/// `r_1`, `r_2` and `alpha`, `beta` are the points and weights of the claims
+/// about the bottom layer's outputs and `final_point` the input layer's
+/// sum-check challenges. Only add and mul gates are folded, so the bottom
+/// layer must have no lookup or custom gates.
+pub fn expected_input_claim<F: PrimeField>(
+    circuit: &Circuit<F, impl LayerSource<F>>,
+    inputs: &[F],
+    r_1: &[F],
+    r_2: &[F],
+    alpha: F,
+    beta: F,
+    final_point: &[F],
+) -> F {
+    let weights = get_folded_selector_weights(r_1, r_2, alpha, beta);
+
+    get_folded_fbc_poly(&circuit.layer(0), inputs, inputs, &weights).evaluate(final_point.to_vec())
+}
+
+fn fold_fbc_selectors<F: PrimeField>(
+    layer: &Layer<F>,
+    weights: &[F],
+) -> (MultilinearPoly<F>, MultilinearPoly<F>) {
+    let (add_i, mul_i) = layer.get_fbc_selectors();
+
+    (
+        fold_selector(&add_i, weights),
+        fold_selector(&mul_i, weights),
+    )
+}
+
+pub fn get_folded_selector_weights<F: PrimeField>(
+    r_b: &[F],
+    r_c: &[F],
+    alpha: F,
+    beta: F,
+) -> Vec<F> {
+    eq_table(r_b)
+        .into_iter()
+        .zip(eq_table(r_c))
+        .map(|(eq_b, eq_c)| (alpha * eq_b) + (beta * eq_c))
+        .collect()
+}
+
+pub fn fold_selector<F: PrimeField>(
+    selector: &MultilinearPoly<F>,
+    weights: &[F],
+) -> MultilinearPoly<F> {
+    let stride = selector.evaluation.len() / weights.len();
+    let mut result = vec![F::zero(); stride];
+
+    for (chunk, weight) in selector.evaluation.chunks_exact(stride).zip(weights) {
+        if weight.is_zero() {
+            continue;
+        }
+
+        for (acc, eval) in result.iter_mut().zip(chunk) {
+            if !eval.is_zero() {
+                *acc += *weight * eval;
+            }
+        }
+    }
+
+    MultilinearPoly::new(result)
+}
+
+/// `eq(r, x)` for every boolean `x`, with `r[0]` bound to the most significant bit
+/// to match `multi_partial_evaluate`.
+fn eq_table<F: PrimeField>(r: &[F]) -> Vec<F> {
+    let mut table = vec![F::one()];
+
+    for r_i in r {
+        table = table
+            .iter()
+            .flat_map(|eq| [*eq * (F::one() - r_i), *eq * r_i])
+            .collect();
+    }
+
+    table
+}
+
+fn get_verifier_selector_evals<F: PrimeField>(
+    layer: &Layer<F>,
+    init_random_challenge: F,
+    sumcheck_random_challenges: &[F],
+) -> (F, F) {
+    let mut all_random_challenges = Vec::with_capacity(1 + sumcheck_random_challenges.len());
+
+    all_random_challenges.push(init_random_challenge);
+    all_random_challenges.extend_from_slice(sumcheck_random_challenges);
+
+    let (add_i, mul_i) = layer.get_fbc_selectors();
+
+    let a_r = add_i.evaluate(all_random_challenges.clone());
+    let m_r = mul_i.evaluate(all_random_challenges);
+
+    (a_r, m_r)
+}
+
+fn get_folded_verifier_selector_evals<F: PrimeField>(
+    layer: &Layer<F>,
+    current_random_challenge: &[F],
+    fold: &ClaimFold<F>,
+) -> (F, F) {
+    let (summed_add_i, summed_mul_i) = fold_fbc_selectors(layer, &fold.weights());
+
+    let a_r = summed_add_i.evaluate(current_random_challenge.to_vec());
+    let m_r = summed_mul_i.evaluate(current_random_challenge.to_vec());
+
+    (a_r, m_r)
+}
+
+/// The fbc selectors of `Layer::get_fbc_selectors` at `(r_a, r_b, r_c)`,
+/// summed gate by gate from the positional wiring without building either
+/// selector table. Each entry of
+/// `output_points` is a weight and an `r_a`, so the folded selectors of
+/// lower layers come out as `alpha * add_i(r_b', ..) + beta * add_i(r_c', ..)`.
+fn get_direct_selector_evals<F: PrimeField>(
+    layer: &Layer<F>,
+    output_points: &[(F, &[F])],
+    bc_challenges: &[F],
+) -> (F, F) {
+    let (a_bits, bc_bits) = layer.selector_widths();
+    let (r_b, r_c) = bc_challenges.split_at(bc_challenges.len() / 2);
+
+    let mut a_r = F::zero();
+    let mut m_r = F::zero();
+
+    for (idx, gate) in layer.gates.iter().enumerate() {
+        let output_eq: F = output_points
+            .iter()
+            .map(|(weight, r_a)| *weight * eq_bits(idx, a_bits, r_a))
+            .sum();
+        let (b, c) = layer.input_wires(idx);
+        let eval = layer.gate_constant(idx)
+            * output_eq
+            * eq_bits(b, bc_bits, r_b)
+            * eq_bits(c, bc_bits, r_c);
+
+        match gate.op {
+            Operation::Add => a_r += eval,
+            Operation::Mul => m_r += eval,
+            Operation::Xor => {
+                a_r += eval;
+                m_r -= eval.double();
+            }
+            #[cfg(feature = "lookup")]
+            Operation::Lookup(id) => m_r += eval * lookup_coefficients(&layer.tables[id])[3],
+            Operation::Custom(_) => {}
+        }
+    }
+
+    (a_r, m_r)
+}
+
+/// The weighted `r_a` points `get_direct_selector_evals` takes: the output
+/// challenge for the output layer and the folded claim points below it,
+/// including a skip connection's.
+fn output_points<'a, F: PrimeField>(
+    fold: Option<&'a ClaimFold<F>>,
+    output_challenge: &'a F,
+) -> Vec<(F, &'a [F])> {
+    match fold {
+        None => vec![(F::one(), std::slice::from_ref(output_challenge))],
+        Some(fold) => {
+            let mut points = vec![(fold.alpha, &fold.r_b[..]), (fold.beta, &fold.r_c[..])];
+            if let Some(skip) = &fold.skip {
+                points.push((skip.gamma, &skip.point));
+            }
+
+            points
+        }
+    }
+}
+
+/// The lookup terms of the fbc poly, `k_i + l_i * w_b + r_i * w_c` with the
+/// selectors of `Layer::get_lookup_selectors`, reduced over the output
+/// variables like the add/mul selectors.
+#[cfg(feature = "lookup")]
+fn get_lookup_terms<F: PrimeField>(
+    layer: &Layer<F>,
+    random_challenge: F,
+    fold: Option<&ClaimFold<F>>,
+    w_b: &[F],
+    w_c: &[F],
+) -> Vec<ProductPoly<F>> {
+    let selectors = layer.get_lookup_selectors();
+    let [constant_i, left_i, right_i] = match fold {
+        None => selectors.map(|selector| selector.partial_evaluate(0, &random_challenge)),
+        Some(fold) => {
+            let weights = fold.weights();
+
+            selectors.map(|selector| fold_selector(&selector, &weights))
+        }
+    };
+
+    let ones = vec![F::one(); w_b.len()];
+    let w_b = tensor_add_mul_polynomials(w_b, &ones, Operation::Mul);
+    let w_c = tensor_add_mul_polynomials(&ones, w_c, Operation::Mul);
+
+    vec![
+        ProductPoly::new(vec![
+            constant_i.evaluation,
+            vec![F::one(); w_b.evaluation.len()],
+        ]),
+        ProductPoly::new(vec![left_i.evaluation, w_b.evaluation]),
+        ProductPoly::new(vec![right_i.evaluation, w_c.evaluation]),
+    ]
+}
+
+/// The selectors of `Layer::get_lookup_selectors` at `(r_a, r_b, r_c)`,
+/// summed gate by gate like `get_direct_selector_evals`.
+#[cfg(feature = "lookup")]
+fn get_lookup_selector_evals<F: PrimeField>(
+    layer: &Layer<F>,
+    output_points: &[(F, &[F])],
+    bc_challenges: &[F],
+) -> [F; 3] {
+    let (a_bits, bc_bits) = layer.selector_widths();
+    let (r_b, r_c) = bc_challenges.split_at(bc_challenges.len() / 2);
+
+    let mut evals = [F::zero(); 3];
+
+    for (idx, gate) in layer.gates.iter().enumerate() {
+        let id = match gate.op {
+            Operation::Lookup(id) => id,
+            _ => continue,
+        };
+
+        let output_eq: F = output_points
+            .iter()
+            .map(|(weight, r_a)| *weight * eq_bits(idx, a_bits, r_a))
+            .sum();
+        let (b, c) = layer.input_wires(idx);
+        let eval = layer.gate_constant(idx)
+            * output_eq
+            * eq_bits(b, bc_bits, r_b)
+            * eq_bits(c, bc_bits, r_c);
+
+        for (acc, coefficient) in evals.iter_mut().zip(lookup_coefficients(&layer.tables[id])) {
+            *acc += eval * coefficient;
+        }
+    }
+
+    evals
+}
+
+/// The custom gate terms of the fbc poly, one product of each selector of
+/// `Layer::get_custom_selectors` with its term's w-factors, reduced over the
+/// output variables like the add/mul selectors.
+fn get_custom_terms<F: PrimeField>(
+    layer: &Layer<F>,
+    random_challenge: F,
+    fold: Option<&ClaimFold<F>>,
+    w_b: &[F],
+    w_c: &[F],
+) -> Vec<ProductPoly<F>> {
+    let weights = fold.map(ClaimFold::weights);
+
+    layer
+        .get_custom_selectors()
+        .into_iter()
+        .map(|(left, right, selector)| {
+            let selector = match &weights {
+                None => selector.partial_evaluate(0, &random_challenge),
+                Some(weights) => fold_selector(&selector, weights),
+            };
+
+            let mut factors = vec![selector.evaluation];
+            factors.extend(monomial_wire_factors(w_b, w_c, left, right));
+
+            ProductPoly::new(factors)
+        })
+        .collect()
+}
+
+/// The custom gate terms at `(r_a, r_b, r_c)`, summed gate by gate like
+/// `get_direct_selector_evals` and weighted by `o_1^left * o_2^right`.
+fn get_custom_claim<F: PrimeField>(
+    layer: &Layer<F>,
+    output_points: &[(F, &[F])],
+    bc_challenges: &[F],
+    (o_1, o_2): (F, F),
+) -> F {
+    let (a_bits, bc_bits) = layer.selector_widths();
+    let (r_b, r_c) = bc_challenges.split_at(bc_challenges.len() / 2);
+
+    let mut claim = F::zero();
+
+    for (idx, gate) in layer.gates.iter().enumerate() {
+        let id = match gate.op {
+            Operation::Custom(id) => id,
+            _ => continue,
+        };
+
+        let output_eq: F = output_points
+            .iter()
+            .map(|(weight, r_a)| *weight * eq_bits(idx, a_bits, r_a))
+            .sum();
+        let (b, c) = layer.input_wires(idx);
+        let eval = layer.gate_constant(idx)
+            * output_eq
+            * eq_bits(b, bc_bits, r_b)
+            * eq_bits(c, bc_bits, r_c);
+
+        for (coefficient, left, right) in layer.gate_fns[id].selector_contribution() {
+            claim += eval * coefficient * o_1.pow([left as u64]) * o_2.pow([right as u64]);
+        }
+    }
+
+    claim
+}
+
+/// The multilinear extension of "equals `value`" over `width` bits, most
+/// significant bit first, evaluated at `point`.
+fn eq_bits<F: PrimeField>(value: usize, width: u32, point: &[F]) -> F {
+    point
+        .iter()
+        .enumerate()
+        .map(|(j, r)| {
+            if (value >> (width as usize - 1 - j)) & 1 == 1 {
+                *r
+            } else {
+                F::one() - r
+            }
+        })
+        .product()
+}
+
+/// The claim the last sum-check round must match. With a single-product fbc
+/// poly, `selector_challenge` is the challenge for the add/mul selector
+/// variable.
+pub(crate) fn combine_verifier_claim<F: PrimeField>(
+    selector_challenge: Option<F>,
+    a_r: F,
+    m_r: F,
+    o_1: F,
+    o_2: F,
+) -> F {
+    match selector_challenge {
+        None => (a_r * (o_1 + o_2)) + (m_r * (o_1 * o_2)),
+        Some(r_s) => {
+            let selector = (F::one() - r_s) * a_r + r_s * m_r;
+            let w = (F::one() - r_s) * (o_1 + o_2) + r_s * (o_1 * o_2);
+
+            selector * w
+        }
+    }
+}
+
+pub(crate) fn evaluate_input_poly<F: PrimeField>(
+    inputs: &[F],
+    sumcheck_random_challenges: &[F],
+) -> (F, F) {
+    let input_poly = MultilinearPoly::new(inputs.to_vec());
+
+    let (r_b, r_c) = sumcheck_random_challenges.split_at(sumcheck_random_challenges.len() / 2);
+    debug_assert!(
+        r_b.len() == r_c.len() && r_b.len() == input_poly.num_of_vars,
+        "{} b and c challenges do not split into two points on the {} input variables",
+        sumcheck_random_challenges.len(),
+        input_poly.num_of_vars
+    );
+
+    let o_1 = input_poly.evaluate(r_b.to_vec());
+    let o_2 = input_poly.evaluate(r_c.to_vec());
+
+    (o_1, o_2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        gkr_circuit::{Gate, FBC_ROUND_DEGREE},
+        test_util::{
+            depth_1_circuit_and_inputs, depth_4_circuit_and_inputs, sample_circuit_and_inputs,
+            SquarePlus,
+        },
+    };
+    use ark_bn254::Fq;
+    use fiat_shamir::challenge_source::FixedChallenges;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_get_fbc_poly() {
+        let gate = Gate::new(Fq::from(2), Fq::from(14), Operation::Add);
+
+        let layer = Layer::new(vec![gate]);
+
+        let r_c = Fq::from(5);
+
+        let w_1_poly = &[Fq::from(2), Fq::from(12)];
+
+        let add_i_r =
+            MultilinearPoly::new(vec![Fq::from(0), Fq::from(-4), Fq::from(0), Fq::from(0)]);
+
+        let mul_i_r = MultilinearPoly::new(vec![Fq::from(0); 4]);
+
+        let fbc_poly = get_fbc_poly(r_c, layer, w_1_poly, w_1_poly);
+
+        let one = ProductPoly::new(vec![
+            add_i_r.evaluation,
+            vec![Fq::from(4), Fq::from(14), Fq::from(14), Fq::from(24)],
+        ]);
+
+        let two = ProductPoly::new(vec![
+            mul_i_r.evaluation,
+            vec![Fq::from(4), Fq::from(24), Fq::from(24), Fq::from(144)],
+        ]);
+
+        let expected_result = SumPoly::new(vec![one, two]);
+
+        assert_eq!(fbc_poly.polys, expected_result.polys);
+    }
+
+    // #[test]
+    // fn test_get_folded_fbc_poly() {
+    //     let gate_1 = Gate::new(Fq::from(1), Fq::from(2), Operation::Mul);
+    //     let gate_2 = Gate::new(Fq::from(3), Fq::from(4), Operation::Mul);
+
+    //     let layer = Layer::new(vec![gate_1, gate_2]);
+
+    //     let w_poly = vec![Fq::from(1), Fq::from(2), Fq::from(3), Fq::from(4)];
+
+    //     let alpha = Fq::from(2);
+    //     let beta = Fq::from(1);
+
+    //     let r_b = &[Fq::from(2)];
+    //     let r_c = &[Fq::from(3)];
+
+    //     let folded_fbc_poly = get_folded_fbc_poly(layer, &w_poly, &w_poly, r_b, r_c, alpha, beta);
+
+    // }
+
+    #[test]
+    fn test_fbc_round_degree() {
+        let gate_1 = Gate::new(Fq::from(1), Fq::from(2), Operation::Mul);
+        let gate_2 = Gate::new(Fq::from(3), Fq::from(4), Operation::Add);
+
+        let layer = Layer::new(vec![gate_1, gate_2]);
+
+        let w_poly = vec![Fq::from(1), Fq::from(2), Fq::from(3), Fq::from(4)];
+
+        let fbc_poly = get_fbc_poly(Fq::from(5), layer.clone(), &w_poly, &w_poly);
+        let weights =
+            get_folded_selector_weights(&[Fq::from(2)], &[Fq::from(3)], Fq::from(2), Fq::from(1));
+        let folded_fbc_poly = get_folded_fbc_poly(&layer, &w_poly, &w_poly, &weights);
+
+        assert_eq!(fbc_poly.round_degree(), FBC_ROUND_DEGREE);
+        assert_eq!(folded_fbc_poly.round_degree(), FBC_ROUND_DEGREE);
+    }
+
+    #[test]
+    fn test_valid_proving_and_verification() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let proof = prove(&mut circuit, &inputs);
+
+        let is_verified = verify(proof, circuit, &inputs);
+
+        assert!(is_verified);
+    }
+
+    #[test]
+    fn test_proving_and_verification_with_gate_constants() {
+        let circuit_structure: Vec<Vec<Operation>> = vec![
+            vec![
+                Operation::Mul,
+                Operation::Mul,
+                Operation::Mul,
+                Operation::Mul,
+            ],
+            vec![Operation::Add, Operation::Add],
+            vec![Operation::Add],
+        ];
+        let constants = vec![None, Some(vec![Fq::from(2), Fq::from(2)]), None];
+
+        let inputs: Vec<Fq> = vec![
+            Fq::from(5),
+            Fq::from(2),
+            Fq::from(2),
+            Fq::from(4),
+            Fq::from(10),
+            Fq::from(0),
+            Fq::from(3),
+            Fq::from(3),
+        ];
+
+        let mut circuit = Circuit::with_constants(circuit_structure, constants);
+
+        let proof = prove(&mut circuit, &inputs);
+
+        assert_eq!(proof.output_poly.evaluation[0], Fq::from(54));
+
+        let is_verified = verify(proof, circuit, &inputs);
+
+        assert!(is_verified);
+    }
+
+    #[test]
+    fn test_prover_and_verifier_transcripts_stay_in_sync() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let proof = prove(&mut circuit, &inputs);
+
+        let trace = circuit.evaluate(&inputs);
+        let mut layers = circuit.layers.clone();
+        layers.reverse();
+        let num_layers = layers.len();
+
+        let mut prover_transcript = Transcript::<Fq>::new();
+        let mut verifier_transcript = Transcript::<Fq>::new();
+
+        let (mut prover_claim, random_challenge) =
+            initiate_protocol(&mut prover_transcript, &proof.output_poly);
+        let (mut verifier_claim, _) =
+            initiate_protocol(&mut verifier_transcript, &proof.output_poly);
+
+        assert_eq!(
+            prover_transcript.state_digest(),
+            verifier_transcript.state_digest()
+        );
+
+        let mut r_b = Vec::new();
+        let mut r_c = Vec::new();
+        let mut alpha = Fq::from(0);
+        let mut beta = Fq::from(0);
+
+        for (idx, layer) in layers.into_iter().enumerate() {
+            prover_transcript.absorb_phase("layer_start", idx);
+            verifier_transcript.absorb_phase("layer_start", idx);
+
+            let w_i = trace.layer_below(idx).to_vec();
+
+            let fbc_poly = if idx == 0 {
+                get_fbc_poly(random_challenge, layer, &w_i, &w_i)
+            } else {
+                let weights = get_folded_selector_weights(&r_b, &r_c, alpha, beta);
+
+                get_folded_fbc_poly(&layer, &w_i, &w_i, &weights)
+            };
+
+            let sum_check_proof = gkr_prove(prover_claim, &fbc_poly, &mut prover_transcript);
+            let sum_check_verify = gkr_verify(
+                proof.proof_polynomials[idx].clone(),
+                verifier_claim,
+                FBC_ROUND_DEGREE,
+                &mut verifier_transcript,
+                |point| fbc_poly.evaluate(point.to_vec()),
+            );
+
+            assert!(sum_check_verify.verified);
+            assert_eq!(
+                prover_transcript.state_digest(),
+                verifier_transcript.state_digest(),
+                "transcripts diverged in sum-check of layer {idx}"
+            );
+
+            if idx == num_layers - 1 {
+                break;
+            }
+
+            let (o_1, o_2) = evaluate_input_poly(&w_i, &sum_check_proof.random_challenges);
+            assert_eq!((o_1, o_2), proof.claimed_evaluations[idx]);
+
+            let mid = sum_check_proof.random_challenges.len() / 2;
+            let (b, c) = sum_check_proof.random_challenges.split_at(mid);
+            r_b = b.to_vec();
+            r_c = c.to_vec();
+
+            for transcript in [&mut prover_transcript, &mut verifier_transcript] {
+                transcript.append_field(o_1);
+                alpha = transcript.get_nonzero_challenge();
+
+                transcript.append_field(o_2);
+                beta = transcript.get_nonzero_challenge();
+            }
+
+            assert_eq!(
+                prover_transcript.state_digest(),
+                verifier_transcript.state_digest(),
+                "transcripts diverged after reducing layer {idx}"
+            );
+
+            prover_claim = (alpha * o_1) + (beta * o_2);
+            verifier_claim = prover_claim;
+        }
+    }
+
+    #[test]
+    fn test_low_memory_proof_matches_standard_proof() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let low_memory_proof = prove_low_memory(&circuit, &inputs);
+        let proof = prove(&mut circuit, &inputs);
+
+        assert_eq!(low_memory_proof, proof);
+        assert!(verify(low_memory_proof, circuit, &inputs));
+    }
+
+    #[test]
+    fn test_prove_and_verify_shared_input() {
+        let (sample, inputs) = sample_circuit_and_inputs();
+        let circuits = [
+            sample,
+            Circuit::new(vec![
+                vec![
+                    Operation::Add,
+                    Operation::Mul,
+                    Operation::Add,
+                    Operation::Mul,
+                ],
+                vec![Operation::Mul, Operation::Add],
+                vec![Operation::Mul],
+            ]),
+        ];
+
+        let proof = prove_shared_input(&circuits, &inputs);
+        assert_eq!(proof.proofs()[0].output_poly().evaluation[0], Fq::from(27));
+        assert_eq!(verify_shared_input(proof, &circuits, &inputs), Ok(()));
+
+        // A standalone proof of the first circuit was not made in the shared
+        // transcript.
+        let mut proof = prove_shared_input(&circuits, &inputs);
+        proof.proofs[0] = prove(&mut circuits[0].clone(), &inputs);
+        assert!(verify_shared_input(proof, &circuits, &inputs).is_err());
+
+        let mut proof = prove_shared_input(&circuits, &inputs);
+        proof.proofs[1].claimed_evaluations[0].1 += Fq::from(1);
+        assert_eq!(
+            verify_shared_input(proof, &circuits, &inputs),
+            Err(GkrError::ClaimMismatch { layer: 0 })
+        );
+
+        let mut proof = prove_shared_input(&circuits, &inputs);
+        proof.proofs.pop();
+        assert_eq!(
+            verify_shared_input(proof, &circuits, &inputs),
+            Err(GkrError::ProofCountMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_shared_input_claims_meet_at_one_point() {
+        let (sample, inputs) = sample_circuit_and_inputs();
+        let circuits = [
+            sample,
+            Circuit::new(vec![
+                vec![
+                    Operation::Add,
+                    Operation::Add,
+                    Operation::Mul,
+                    Operation::Mul,
+                ],
+                vec![Operation::Add, Operation::Mul],
+                vec![Operation::Add],
+            ]),
+        ];
+        let input_poly = MultilinearPoly::new(inputs.to_vec());
+
+        // Both circuits' four input claims are checked by one evaluation of
+        // the inputs, at one shared point...
+        let points = std::cell::RefCell::new(Vec::new());
+        let proof = prove_shared_input(&circuits, &inputs);
+        let result = verify_shared_input_with(proof, &circuits, &inputs, |point| {
+            points.borrow_mut().push(point.to_vec());
+            input_poly.evaluate(point.to_vec())
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(points.borrow().len(), 1);
+
+        let proof = prove_shared_input(&circuits, &inputs);
+        // ...which is none of the points the first circuit's sum-checks end at.
+        let verified = verify_with_input_claims(
+            &proof.proofs[0],
+            &circuits[0],
+            proof.input_claims[0],
+            &mut shared_transcript(&inputs),
+        )
+        .unwrap();
+        let (r_b, r_c) = verified.input_points();
+        assert_ne!(points.borrow()[0], r_b);
+        assert_ne!(points.borrow()[0], r_c);
+
+        let mut proof = prove_shared_input(&circuits, &inputs);
+        proof.input_claims[1].0 += Fq::from(1);
+        assert_eq!(
+            verify_shared_input(proof, &circuits, &inputs),
+            Err(GkrError::ClaimMismatch { layer: 2 })
+        );
+
+        let mut proof = prove_shared_input(&circuits, &inputs);
+        proof.input_reduction[0][0] += Fq::from(1);
+        assert_eq!(
+            verify_shared_input(proof, &circuits, &inputs),
+            Err(GkrError::InputClaimMismatch)
+        );
+
+        let mut proof = prove_shared_input(&circuits, &inputs);
+        proof.input_reduction.pop();
+        assert_eq!(
+            verify_shared_input(proof, &circuits, &inputs),
+            Err(GkrError::InputClaimMismatch)
+        );
+    }
+
+    fn shared_transcript(inputs: &[Fq]) -> Transcript<Fq> {
+        let mut transcript = Transcript::new();
+        transcript.append_fields(inputs);
+        transcript
+    }
+
+    #[test]
+    fn test_proofs_are_bound_to_their_nonce() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let proof = prove_with_nonce(&mut circuit, &inputs, b"nonce A");
+        assert_eq!(
+            verify_with_nonce(proof, circuit.clone(), &inputs, b"nonce A"),
+            Ok(())
+        );
+
+        let proof = prove_with_nonce(&mut circuit, &inputs, b"nonce A");
+        assert!(verify_with_nonce(proof, circuit.clone(), &inputs, b"nonce B").is_err());
+
+        let proof = prove_with_nonce(&mut circuit, &inputs, b"nonce A");
+        assert!(!verify(proof, circuit.clone(), &inputs));
+
+        let proof = prove_with_nonce(&mut circuit, &inputs, b"nonce A");
+        assert!(verify_deterministic(proof, circuit.clone(), &inputs, b"nonce A").is_err());
+
+        let proof = prove_deterministic(&mut circuit, &inputs, b"nonce A");
+        assert!(verify_with_nonce(proof, circuit, &inputs, b"nonce A").is_err());
+    }
+
+    #[test]
+    fn test_prove_with_trace() {
+        let (mut circuit, inputs) = depth_4_circuit_and_inputs();
+
+        let (proof, trace) = prove_with_trace(&mut circuit, &inputs);
+
+        assert_eq!(trace, circuit.evaluate(&inputs));
+        assert_eq!(trace.depth(), circuit.depth());
+        assert_eq!(proof, prove(&mut circuit, &inputs));
+        assert!(verify(proof, circuit, &inputs));
+    }
+
+    #[test]
+    fn test_structurally_eq_ignores_trailing_zero_coefficients() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let proof = prove(&mut circuit, &inputs);
+
+        let round_poly = &proof.proof_polynomials[1][0];
+        let points = round_poly
+            .iter()
+            .enumerate()
+            .map(|(i, y)| (Fq::from(i as u64), *y))
+            .collect();
+        let next_evaluation = UnivariatePoly::interpolate(points).evaluate(Fq::from(3));
+
+        let mut padded = prove(&mut circuit, &inputs);
+        padded.proof_polynomials[1][0].push(next_evaluation);
+
+        assert_ne!(proof, padded);
+        assert!(proof.structurally_eq(&padded));
+        assert!(padded.structurally_eq(&proof));
+
+        assert_ne!(next_evaluation, Fq::from(0));
+        let mut zero_padded = prove(&mut circuit, &inputs);
+        zero_padded.proof_polynomials[1][0].push(Fq::from(0));
+        assert!(!proof.structurally_eq(&zero_padded));
+
+        let mut tampered = prove(&mut circuit, &inputs);
+        tampered.proof_polynomials[1][0][0] += Fq::from(1);
+        assert!(!proof.structurally_eq(&tampered));
+
+        let mut truncated = prove(&mut circuit, &inputs);
+        truncated.proof_polynomials[2].pop();
+        assert!(!proof.structurally_eq(&truncated));
+    }
+
+    #[test]
+    fn test_verify_and_squeeze() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let first = verify_and_squeeze(prove(&mut circuit, &inputs), circuit.clone(), &inputs);
+        let second = verify_and_squeeze(prove(&mut circuit, &inputs), circuit.clone(), &inputs);
+        assert!(first.is_ok());
+        assert_eq!(first, second);
+
+        let mut tampered = prove(&mut circuit, &inputs);
+        tampered.claimed_evaluations[0].0 += Fq::from(1);
+        assert_eq!(
+            verify_and_squeeze(tampered, circuit.clone(), &inputs),
+            Err(GkrError::ClaimMismatch { layer: 0 })
+        );
+
+        let mut other_inputs = inputs.clone();
+        other_inputs[0] += Fq::from(1);
+        let other = verify_and_squeeze(
+            prove(&mut circuit, &other_inputs),
+            circuit.clone(),
+            &other_inputs,
+        );
+        assert!(other.is_ok());
+        assert_ne!(other, first);
+    }
+
+    #[test]
+    fn test_deterministic_proofs_are_byte_identical() {
+        let (mut circuit, inputs) = depth_4_circuit_and_inputs();
+
+        let first = prove_deterministic(&mut circuit, &inputs, b"test vector 1").serialize();
+        let second = prove_deterministic(&mut circuit, &inputs, b"test vector 1").serialize();
+        let other_seed = prove_deterministic(&mut circuit, &inputs, b"test vector 2").serialize();
+
+        assert_eq!(first, second);
+        assert_ne!(first, other_seed);
+
+        let proof = Proof::deserialize_versioned(&first).unwrap();
+        assert_eq!(
+            verify_deterministic(proof, circuit.clone(), &inputs, b"test vector 1"),
+            Ok(())
+        );
+
+        let proof = Proof::deserialize_versioned(&first).unwrap();
+        assert!(verify_deterministic(proof, circuit, &inputs, b"test vector 2").is_err());
+    }
+
+    #[test]
+    fn test_prove_and_verify_xor_gates() {
+        let structure = vec![
+            vec![
+                Operation::Xor,
+                Operation::Xor,
+                Operation::Mul,
+                Operation::Xor,
+            ],
+            vec![Operation::Xor, Operation::Add],
+            vec![Operation::Mul],
+        ];
+        let inputs: Vec<Fq> = [1, 0, 1, 1, 1, 1, 0, 1].into_iter().map(Fq::from).collect();
+
+        let mut circuit = Circuit::new(structure);
+        let evaluations = circuit.evaluate(&inputs);
+
+        assert_eq!(
+            evaluations[0],
+            vec![Fq::from(1), Fq::from(0), Fq::from(1), Fq::from(1)]
+        );
+        assert_eq!(evaluations[2], vec![Fq::from(2)]);
+
+        let proof = prove(&mut circuit, &inputs);
+        assert!(verify(proof, circuit.clone(), &inputs));
+
+        for config in [
+            GkrConfig {
+                selector_evaluation: SelectorEvaluation::Direct,
+                ..Default::default()
+            },
+            GkrConfig {
+                fbc_construction: FbcConstruction::SingleProduct,
+                ..Default::default()
+            },
+        ] {
+            let proof = prove_with_config(&mut circuit, &inputs, &config).unwrap();
+            assert!(verify_with_config(proof, circuit.clone(), &inputs, &config).is_ok());
+        }
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.claimed_evaluations[0].1 += Fq::from(1);
+        assert!(!verify(proof, circuit, &inputs));
+    }
+
+    #[cfg(feature = "lookup")]
+    #[test]
+    fn test_prove_and_verify_lookup_gates() {
+        const NOT: usize = 0;
+        const AND: usize = 1;
+
+        let lookup_circuit = |not_table: [u64; 2]| {
+            let mut circuit = Circuit::new(vec![
+                vec![
+                    Operation::Lookup(NOT),
+                    Operation::Mul,
+                    Operation::Lookup(NOT),
+                    Operation::Add,
+                ],
+                vec![Operation::Lookup(AND), Operation::Lookup(NOT)],
+                vec![Operation::Mul],
+            ]);
+
+            let not = circuit.register_table(not_table.into_iter().map(Fq::from).collect());
+            let and = circuit.register_table([2, 3, 5, 7].into_iter().map(Fq::from).collect());
+            assert_eq!((not, and), (NOT, AND));
+
+            circuit
+        };
+        let inputs: Vec<Fq> = [0, 4, 1, 1, 1, 0, 0, 0].into_iter().map(Fq::from).collect();
+
+        let mut circuit = lookup_circuit([1, 0]);
+        let evaluations = circuit.evaluate(&inputs);
+
+        assert_eq!(
+            evaluations[0],
+            vec![Fq::from(1), Fq::from(1), Fq::from(0), Fq::from(0)]
+        );
+        assert_eq!(evaluations[1], vec![Fq::from(7), Fq::from(1)]);
+        assert_eq!(evaluations[2], vec![Fq::from(7)]);
+
+        let proof = prove(&mut circuit, &inputs);
+        assert_eq!(proof.output_poly().evaluation[0], Fq::from(7));
+        assert!(verify(proof, circuit.clone(), &inputs));
+
+        let config = GkrConfig {
+            selector_evaluation: SelectorEvaluation::Direct,
+            ..Default::default()
+        };
+        let proof = prove_with_config(&mut circuit, &inputs, &config).unwrap();
+        assert!(verify_with_config(proof, circuit.clone(), &inputs, &config).is_ok());
+
+        let proof = prove(&mut circuit, &inputs);
+        assert!(!verify(proof, lookup_circuit([1, 1]), &inputs));
+    }
+
+    #[test]
+    fn test_prove_and_verify_custom_gates() {
+        let custom_circuit = |scale: u64| {
+            let mut circuit = Circuit::new(vec![
+                vec![
+                    Operation::Custom(0),
+                    Operation::Mul,
+                    Operation::Add,
+                    Operation::Custom(0),
+                ],
+                vec![Operation::Custom(0), Operation::Mul],
+                vec![Operation::Custom(0)],
+            ]);
+
+            assert_eq!(circuit.register_gate(Arc::new(SquarePlus { scale })), 0);
+
+            circuit
+        };
+        let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+        let mut circuit = custom_circuit(1);
+        let evaluations = circuit.evaluate(&inputs);
+
+        assert_eq!(
+            evaluations[0],
+            vec![Fq::from(3), Fq::from(12), Fq::from(11), Fq::from(57)]
+        );
+        assert_eq!(evaluations[1], vec![Fq::from(21), Fq::from(627)]);
+        assert_eq!(evaluations[2], vec![Fq::from(1068)]);
+
+        let proof = prove(&mut circuit, &inputs);
+        assert!(proof
+            .proof_polynomials
+            .iter()
+            .flatten()
+            .all(|round_poly| round_poly.len() == 4));
+        assert_eq!(verify_parallel(&proof, &circuit, &inputs), Ok(()));
+        assert!(verify(proof, circuit.clone(), &inputs));
+
+        let config = GkrConfig {
+            selector_evaluation: SelectorEvaluation::Direct,
+            ..Default::default()
+        };
+        let proof = prove_with_config(&mut circuit, &inputs, &config).unwrap();
+        assert!(verify_with_config(proof, circuit.clone(), &inputs, &config).is_ok());
+
+        let single_product = GkrConfig {
+            fbc_construction: FbcConstruction::SingleProduct,
+            ..Default::default()
+        };
+        assert_eq!(
+            prove_with_config(&mut circuit, &inputs, &single_product),
+            Err(GkrError::UnsupportedConstruction { layer: 0 })
+        );
+        assert_eq!(
+            verify_with_config(
+                prove(&mut circuit, &inputs),
+                circuit.clone(),
+                &inputs,
+                &single_product
+            ),
+            Err(GkrError::UnsupportedConstruction { layer: 0 })
+        );
+
+        let proof = prove(&mut circuit, &inputs);
+        assert!(!verify(proof, custom_circuit(2), &inputs));
+
+        let proof = prove(&mut circuit, &inputs);
+        let mut tampered = inputs.clone();
+        tampered[0] += Fq::from(1);
+        assert!(!verify(proof, circuit.clone(), &tampered));
+    }
+
+    #[test]
+    fn test_verify_with_input_claims() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let proof = prove(&mut circuit, &inputs);
+
+        let challenges =
+            verify_with_challenges(prove(&mut circuit, &inputs), circuit.clone(), &inputs).unwrap();
+        let (r_b, r_c) = challenges.input_points();
+        let input_poly = MultilinearPoly::new(inputs.clone());
+        let input_claims = (
+            input_poly.evaluate(r_b.to_vec()),
+            input_poly.evaluate(r_c.to_vec()),
+        );
+
+        assert_eq!(
+            verify_with_input_claims(&proof, &circuit, input_claims, &mut Transcript::new()),
+            Ok(challenges)
+        );
+        assert_eq!(
+            verify_with_input_claims(
+                &proof,
+                &circuit,
+                (input_claims.0 + Fq::from(1), input_claims.1),
+                &mut Transcript::new()
+            ),
+            Err(GkrError::ClaimMismatch { layer: 2 })
+        );
+
+        circuit.add_skip_connection(1, 0).unwrap();
+        let proof = prove(&mut circuit, &inputs);
+        assert_eq!(
+            verify_with_input_claims(&proof, &circuit, input_claims, &mut Transcript::new()),
+            Err(GkrError::InputsNotAvailable { layer: 1 })
+        );
+    }
+
+    #[test]
+    fn test_prove_and_verify_skip_connections() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        circuit.add_skip_connection(2, 0).unwrap();
+
+        assert_eq!(circuit.evaluate(&inputs)[2], vec![Fq::from(26)]);
+
+        let proof = prove(&mut circuit, &inputs);
+        assert_eq!(proof.skip_evaluations.len(), 1);
+        assert_eq!(verify_parallel(&proof, &circuit, &inputs), Ok(()));
+        assert!(verify(proof, circuit.clone(), &inputs));
+
+        for config in [
+            GkrConfig {
+                selector_evaluation: SelectorEvaluation::Direct,
+                ..Default::default()
+            },
+            GkrConfig {
+                fbc_construction: FbcConstruction::SingleProduct,
+                ..Default::default()
+            },
+        ] {
+            let proof = prove_with_config(&mut circuit, &inputs, &config).unwrap();
+            assert!(verify_with_config(proof, circuit.clone(), &inputs, &config).is_ok());
+        }
+
+        let (plain, _) = sample_circuit_and_inputs();
+        assert!(!verify(prove(&mut circuit, &inputs), plain, &inputs));
+
+        let mut tampered = prove(&mut circuit, &inputs);
+        tampered.skip_evaluations[0] += Fq::from(1);
+        assert_eq!(
+            verify_parallel(&tampered, &circuit, &inputs),
+            Err(GkrError::ClaimMismatch { layer: 0 })
+        );
+
+        tampered.skip_evaluations.clear();
+        assert_eq!(
+            verify_parallel(&tampered, &circuit, &inputs),
+            Err(GkrError::MalformedProof { layer: 0 })
+        );
+
+        // A skip connection over the inputs sends no evaluation: the verifier
+        // reads the inputs itself.
+        circuit.add_skip_connection(1, 1).unwrap();
+        assert_eq!(
+            circuit.evaluate(&inputs)[1],
+            vec![Fq::from(18), Fq::from(4)]
+        );
+
+        let proof = prove(&mut circuit, &inputs);
+        assert_eq!(proof.skip_evaluations.len(), 1);
+        assert!(verify(proof, circuit.clone(), &inputs));
+    }
+
+    #[test]
+    fn test_prove_padding_zero_pads_short_inputs() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let short = &inputs[..6];
+        let mut padded = short.to_vec();
+        padded.extend([Fq::from(0), Fq::from(0)]);
+
+        let mut proof = None;
+        let logs = capture_logs(|| proof = prove_padding(&mut circuit, short).ok());
+        let proof = proof.unwrap();
+        assert_eq!(proof, prove(&mut circuit, &padded));
+        assert!(verify(proof, circuit.clone(), &padded));
+        assert_eq!(
+            logs,
+            vec!["zero-padding 6 inputs to the circuit's input width 8".to_string()]
+        );
+
+        let logs = capture_logs(|| assert!(prove_padding(&mut circuit, &inputs).is_ok()));
+        assert!(logs.is_empty());
+
+        let proof = prove_padding(&mut circuit, short).unwrap();
+        assert_eq!(
+            verify_with_config(proof, circuit.clone(), short, &GkrConfig::default()),
+            Ok(())
+        );
+
+        assert_eq!(
+            prove_padding(&mut circuit, &inputs).unwrap(),
+            prove(&mut circuit, &inputs)
+        );
+
+        let mut long = inputs.clone();
+        long.push(Fq::from(1));
+        assert_eq!(
+            prove_padding(&mut circuit, &long),
+            Err(GkrError::InputLengthMismatch {
+                expected: 8,
+                actual: 9
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_with_mode_reports_every_failing_layer() {
+        let (mut circuit, inputs) = depth_4_circuit_and_inputs();
+        let proof = prove(&mut circuit, &inputs);
+
+        for mode in [VerifyMode::FailFast, VerifyMode::CheckAll] {
+            let report = verify_with_mode(&proof, &circuit, &inputs, mode).unwrap();
+
+            assert!(report.is_ok());
+            assert_eq!(report.layers.len(), 4);
+        }
+
+        let mut tampered = prove(&mut circuit, &inputs);
+        tampered.proof_polynomials[1][0][2] += Fq::from(1);
+        tampered.proof_polynomials[3][0][2] += Fq::from(1);
+
+        let fail_fast =
+            verify_with_mode(&tampered, &circuit, &inputs, VerifyMode::FailFast).unwrap();
+        assert_eq!(
+            fail_fast.layers,
+            vec![Ok(()), Err(GkrError::SumCheckFailed { layer: 1 })]
+        );
+
+        let check_all =
+            verify_with_mode(&tampered, &circuit, &inputs, VerifyMode::CheckAll).unwrap();
+        assert_eq!(check_all.layers.len(), 4);
+        assert_eq!(check_all.layers[0], Ok(()));
+        assert_eq!(
+            check_all.layers[1],
+            Err(GkrError::SumCheckFailed { layer: 1 })
+        );
+        assert!(check_all.layers[3].is_err());
+        assert!(!check_all.is_ok());
+        assert_eq!(check_all.failures()[0], fail_fast.failures()[0]);
+
+        assert_eq!(
+            verify_with_mode(&tampered, &circuit, &inputs[1..], VerifyMode::CheckAll),
+            Err(GkrError::InputLengthMismatch {
+                expected: 16,
+                actual: 15
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_in_outer_transcript() {
+        let circuit_structure: Vec<Vec<Operation>> =
+            vec![vec![Operation::Mul, Operation::Mul], vec![Operation::Add]];
+
+        let inputs: Vec<Fq> = vec![Fq::from(1), Fq::from(2), Fq::from(3), Fq::from(4)];
+
+        let mut circuit = Circuit::new(circuit_structure);
+
+        let mut prover_transcript = Transcript::<Fq>::new();
+        prover_transcript.append("outer protocol".as_bytes());
+        let proof = prove_with_transcript(&mut circuit, &inputs, &mut prover_transcript);
+
+        let mut verifier_transcript = Transcript::<Fq>::new();
+        verifier_transcript.append("outer protocol".as_bytes());
+        let result =
+            verify_in_transcript(proof, circuit.clone(), &inputs, &mut verifier_transcript);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            prover_transcript.get_random_challenge(),
+            verifier_transcript.get_random_challenge()
+        );
+
+        let proof = prove_with_transcript(&mut circuit, &inputs, &mut Transcript::new());
+
+        let mut verifier_transcript = Transcript::<Fq>::new();
+        verifier_transcript.append("outer protocol".as_bytes());
+        let result = verify_in_transcript(proof, circuit, &inputs, &mut verifier_transcript);
+
+        assert_eq!(result, Err(GkrError::SumCheckFailed { layer: 0 }));
+    }
+
+    #[test]
+    fn test_prove_and_verify_through_a_writing_transcript() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let mut prover_transcript = Transcript::<Fq, Vec<u8>>::with_writer(Vec::new());
+        let proof = prove_with_transcript(&mut circuit, &inputs, &mut prover_transcript);
+
+        assert_eq!(proof, prove(&mut circuit, &inputs));
+
+        let mut verifier_transcript = Transcript::<Fq, Vec<u8>>::with_writer(Vec::new());
+        let result = verify_in_transcript(proof, circuit, &inputs, &mut verifier_transcript);
+
+        assert_eq!(result, Ok(()));
+
+        let prover_bytes = prover_transcript.into_writer();
+        assert!(!prover_bytes.is_empty());
+        assert_eq!(prover_bytes, verifier_transcript.into_writer());
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn test_prove_with_stats() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let (proof, stats) = prove_with_stats(&mut circuit, &inputs);
+
+        assert_eq!(stats.layers.len(), circuit.layers.len());
+        assert!(stats
+            .layers
+            .iter()
+            .all(|layer| layer.sum_check > Duration::ZERO));
+        assert_eq!(stats.num_gates, 7);
+        assert!(stats.total >= stats.evaluation);
+
+        let throughput = stats.gates_per_second().unwrap();
+        assert!(throughput.is_finite() && throughput > 0.0);
+        assert!(verify(proof, circuit, &inputs));
+
+        let unmeasured = ProveStats {
+            num_gates: 7,
+            ..ProveStats::default()
+        };
+        assert_eq!(unmeasured.gates_per_second(), None);
+    }
+
+    #[test]
+    fn test_folded_selectors_match_partial_evaluation() {
+        let (mut circuit, inputs) = depth_4_circuit_and_inputs();
+        let alpha = Fq::from(3);
+        let beta = Fq::from(7);
+
+        for layer in circuit.layers.iter().rev().skip(1) {
+            let n_vars = layer.gates.len().ilog2() as usize;
+            let r_b: Vec<Fq> = (0..n_vars).map(|i| Fq::from(i as u64 + 2)).collect();
+            let r_c: Vec<Fq> = (0..n_vars).map(|i| Fq::from(i as u64 + 11)).collect();
+
+            let (add_i, mul_i) = get_folded_selectors(layer, &r_b, &r_c, alpha, beta);
+
+            for (op, folded) in [(Operation::Add, add_i), (Operation::Mul, mul_i)] {
+                let selector = layer.get_add_mul_i(op);
+                let expected = selector.multi_partial_evaluate(&r_b).scale(alpha)
+                    + selector.multi_partial_evaluate(&r_c).scale(beta);
+
+                assert_eq!(folded.first_difference(&expected), None);
+            }
+        }
+
+        let proof = prove(&mut circuit, &inputs);
+
+        assert!(verify(proof, circuit, &inputs));
+    }
+
+    #[test]
+    fn test_direct_selector_evals_match_tables() {
+        let (circuit, _) = depth_4_circuit_and_inputs();
+        let alpha = Fq::from(3);
+        let beta = Fq::from(7);
+
+        for (i, layer) in circuit.layers_top_down().enumerate() {
+            let (_, bc_bits) = layer.selector_widths();
+            let bc: Vec<Fq> = (0..2 * bc_bits).map(|j| Fq::from(j as u64 + 5)).collect();
+
+            if i == 0 {
+                let r = Fq::from(9);
+
+                assert_eq!(
+                    get_direct_selector_evals(&layer, &[(Fq::from(1), &[r])], &bc),
+                    get_verifier_selector_evals(&layer, r, &bc)
+                );
+            } else {
+                let n_vars = layer.gates.len().ilog2() as usize;
+                let prev: Vec<Fq> = (0..2 * n_vars).map(|j| Fq::from(j as u64 + 17)).collect();
+                let (prev_r_b, prev_r_c) = prev.split_at(n_vars);
+
+                assert_eq!(
+                    get_direct_selector_evals(&layer, &[(alpha, prev_r_b), (beta, prev_r_c)], &bc),
+                    get_folded_verifier_selector_evals(
+                        &layer,
+                        &bc,
+                        &ClaimFold {
+                            r_b: prev_r_b.to_vec(),
+                            r_c: prev_r_c.to_vec(),
+                            alpha,
+                            beta,
+                            skip: None,
+                        }
+                    )
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_with_direct_selector_evaluation() {
+        let direct = GkrConfig {
+            selector_evaluation: SelectorEvaluation::Direct,
+            ..Default::default()
+        };
+
+        for (mut circuit, inputs) in [
+            depth_1_circuit_and_inputs(),
+            sample_circuit_and_inputs(),
+            depth_4_circuit_and_inputs(),
+        ] {
+            let proof = prove(&mut circuit, &inputs);
+            assert!(verify_with_config(proof, circuit.clone(), &inputs, &direct).is_ok());
+        }
+
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.claimed_evaluations[1].0 += Fq::from(1);
+        let table_result = verify_with_config(proof, circuit.clone(), &inputs, &Default::default());
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.claimed_evaluations[1].0 += Fq::from(1);
+        let direct_result = verify_with_config(proof, circuit, &inputs, &direct);
+
+        assert!(table_result.is_err());
+        assert_eq!(direct_result, table_result);
+    }
+
+    #[test]
+    fn test_verify_rejects_empty_round_polys() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        for layer in 0..circuit.depth() {
+            let mut proof = prove(&mut circuit, &inputs);
+            proof.proof_polynomials[layer].clear();
+
+            assert_eq!(
+                verify_in_transcript(proof, circuit.clone(), &inputs, &mut Transcript::new()),
+                Err(GkrError::MalformedProof { layer })
+            );
+        }
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.proof_polynomials.pop();
+
+        assert_eq!(
+            verify_parallel(&proof, &circuit, &inputs),
+            Err(GkrError::MalformedProof { layer: 2 })
+        );
+        assert_eq!(
+            verify_in_transcript(proof, circuit, &inputs, &mut Transcript::new()),
+            Err(GkrError::MalformedProof { layer: 2 })
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_proofs() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.proof_polynomials[1][2].push(Fq::from(0));
+        assert_eq!(
+            verify_in_transcript(proof, circuit.clone(), &inputs, &mut Transcript::new()),
+            Err(GkrError::MalformedProof { layer: 1 })
+        );
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.proof_polynomials[0].push(vec![Fq::from(0); FBC_ROUND_DEGREE + 1]);
+        assert_eq!(
+            verify_parallel(&proof, &circuit, &inputs),
+            Err(GkrError::MalformedProof { layer: 0 })
+        );
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.proof_polynomials.push(Vec::new());
+        assert!(!verify(proof, circuit.clone(), &inputs));
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.claimed_evaluations.pop();
+        assert_eq!(
+            verify_in_transcript(proof, circuit, &inputs, &mut Transcript::new()),
+            Err(GkrError::MalformedProof { layer: 1 })
+        );
+    }
+
+    #[test]
+    fn test_verify_parallel_agrees_with_verify() {
+        type Tamper = fn(&mut Proof<Fq>, &mut Vec<Fq>);
+
+        let tamperings: [Tamper; 4] = [
+            |proof, _| proof.proof_polynomials[1][0][2] += Fq::from(1),
+            |proof, _| proof.claimed_evaluations[1].0 += Fq::from(1),
+            |_, inputs| inputs[0] += Fq::from(1),
+            |_, inputs| {
+                inputs.pop();
+            },
+        ];
+
+        for (mut circuit, inputs) in [sample_circuit_and_inputs(), depth_4_circuit_and_inputs()] {
+            let proof = prove(&mut circuit, &inputs);
+            assert_eq!(verify_parallel(&proof, &circuit, &inputs), Ok(()));
+
+            for tamper in tamperings {
+                let mut proof = prove(&mut circuit, &inputs);
+                let mut inputs = inputs.clone();
+                tamper(&mut proof, &mut inputs);
+
+                let parallel = verify_parallel(&proof, &circuit, &inputs);
+                let sequential =
+                    verify_in_transcript(proof, circuit.clone(), &inputs, &mut Transcript::new());
+
+                assert!(sequential.is_err());
+                assert_eq!(parallel, sequential);
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_with_challenges_matches_prover() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let trace = circuit.evaluate(&inputs);
+        let w_0 = trace.output().to_vec();
+
+        let (proof, prover_challenges) = prove_layers(
+            &circuit,
+            w_0,
+            &mut Transcript::new(),
+            FbcConstruction::default(),
+            |idx| trace.layer_below(idx).to_vec(),
+            &mut |_, _, _| {},
+        );
+
+        let verifier_challenges = verify_with_challenges(proof, circuit, &inputs).unwrap();
+
+        assert_eq!(verifier_challenges, prover_challenges);
+        assert_eq!(verifier_challenges.layer_challenges.len(), 3);
+        assert_eq!(verifier_challenges.alphas_betas.len(), 2);
+    }
+
+    #[test]
+    fn test_layer_below_boundaries() {
+        let evaluations = vec![
+            vec![Fq::from(27)],
+            vec![Fq::from(18), Fq::from(9)],
+            vec![Fq::from(10), Fq::from(8), Fq::from(0), Fq::from(9)],
+        ];
+        let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+        assert_eq!(
+            layer_below(&evaluations, &inputs, 0, 3),
+            &evaluations[1][..]
+        );
+        assert_eq!(
+            layer_below(&evaluations, &inputs, 1, 3),
+            &evaluations[2][..]
+        );
+        assert_eq!(layer_below(&evaluations, &inputs, 2, 3), &inputs[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_layer_below_rejects_index_past_input_layer() {
+        let evaluations = vec![vec![Fq::from(3)]];
+        let inputs = vec![Fq::from(1), Fq::from(2)];
+
+        let _ = layer_below(&evaluations, &inputs, 1, 1);
+    }
+
+    fn padded_input_circuit() -> Circuit<Fq> {
+        Circuit::new(vec![
+            vec![
+                Operation::Add,
+                Operation::Add,
+                Operation::Add,
+                Operation::Add,
+            ],
+            vec![Operation::Mul, Operation::Mul],
+            vec![Operation::Add],
+        ])
+    }
+
+    #[test]
+    fn test_prove_and_verify_padded_inputs() {
+        let inputs: Vec<Fq> = (1..=6).map(Fq::from).collect();
+
+        let zero_pad = GkrConfig {
+            padding: PaddingPolicy::ZeroPad,
+            ..Default::default()
+        };
+        let repeat_last = GkrConfig {
+            padding: PaddingPolicy::RepeatLast,
+            ..Default::default()
+        };
+
+        let mut circuit = padded_input_circuit();
+        let proof = prove_with_config(&mut circuit, &inputs, &zero_pad).unwrap();
+        assert_eq!(proof.output_poly.evaluation[0], Fq::from(21));
+        assert!(verify_with_config(proof, circuit, &inputs, &zero_pad).is_ok());
+
+        let mut circuit = padded_input_circuit();
+        let proof = prove_with_config(&mut circuit, &inputs, &repeat_last).unwrap();
+        assert_eq!(proof.output_poly.evaluation[0], Fq::from(153));
+        assert!(verify_with_config(proof, circuit, &inputs, &repeat_last).is_ok());
+
+        let mut circuit = padded_input_circuit();
+        let proof = prove_with_config(&mut circuit, &inputs, &zero_pad).unwrap();
+        assert!(verify_with_config(proof, circuit, &inputs, &repeat_last).is_err());
+
+        let error = GkrConfig {
+            padding: PaddingPolicy::Error,
+            ..Default::default()
+        };
+        let mut circuit = padded_input_circuit();
+        let proof = prove_with_config(&mut circuit, &inputs, &zero_pad).unwrap();
+        assert!(verify_with_config(proof, circuit, &inputs, &error).is_err());
+    }
+
+    #[test]
+    fn test_prove_rejects_unpadded_inputs_under_error_policy() {
+        let inputs: Vec<Fq> = (1..=6).map(Fq::from).collect();
+        let config = GkrConfig {
+            padding: PaddingPolicy::Error,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            prove_with_config(&mut padded_input_circuit(), &inputs, &config),
+            Err(GkrError::InputLengthMismatch {
+                expected: 8,
+                actual: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_single_product_fbc_construction_verifies() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let sum_of_products = GkrConfig::default();
+        let single_product = GkrConfig {
+            fbc_construction: FbcConstruction::SingleProduct,
+            ..Default::default()
+        };
+
+        let proof = prove_with_config(&mut circuit, &inputs, &sum_of_products).unwrap();
+        assert!(verify_with_config(proof, circuit.clone(), &inputs, &sum_of_products).is_ok());
+
+        let proof = prove_with_config(&mut circuit, &inputs, &single_product).unwrap();
+        assert_eq!(proof.proof_polynomials[0].len(), 3);
+        assert_eq!(proof.output_poly.evaluation[0], Fq::from(27));
+        assert!(verify_with_config(proof, circuit.clone(), &inputs, &single_product).is_ok());
+
+        let proof = prove_with_config(&mut circuit, &inputs, &single_product).unwrap();
+        assert!(verify_with_config(proof, circuit, &inputs, &sum_of_products).is_err());
+    }
+
+    #[test]
+    fn test_config_rejects_oversized_selectors() {
+        let inputs: Vec<Fq> = (1..=8).map(Fq::from).collect();
+        let config = GkrConfig {
+            max_selector_size: 64,
+            ..Default::default()
+        };
+
+        let mut circuit = padded_input_circuit();
+
+        assert_eq!(
+            prove_with_config(&mut circuit, &inputs, &config),
+            Err(GkrError::SelectorTooLarge {
+                layer: 2,
+                requested: 256
+            })
+        );
+
+        let proof = prove(&mut circuit, &inputs);
+
+        assert_eq!(
+            verify_with_config(proof, circuit, &inputs, &config),
+            Err(GkrError::SelectorTooLarge {
+                layer: 2,
+                requested: 256
+            })
+        );
+    }
+
+    #[test]
+    fn test_prove_and_verify_with_fixed_challenges() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let challenges: Vec<Fq> = (1..=32).map(Fq::from).collect();
+        let shifted_challenges: Vec<Fq> = (2..=33).map(Fq::from).collect();
+
+        let proof = prove_interactive(
+            &mut circuit,
+            &inputs,
+            &mut FixedChallenges::new(challenges.clone()),
+        );
+        let repeated_proof = prove_interactive(
+            &mut circuit,
+            &inputs,
+            &mut FixedChallenges::new(challenges.clone()),
+        );
+
+        assert_eq!(proof, repeated_proof);
+
+        assert_eq!(
+            verify_interactive(
+                proof,
+                circuit.clone(),
+                &inputs,
+                &mut FixedChallenges::new(challenges)
+            ),
+            Ok(())
+        );
+        assert!(verify_interactive(
+            repeated_proof,
+            circuit,
+            &inputs,
+            &mut FixedChallenges::new(shifted_challenges)
+        )
+        .is_err());
+    }
+
+    /// Forwards to a Keccak transcript, but drops the phase marker of
+    /// `layer`.
+    struct DroppedPhase {
+        transcript: Transcript<Fq>,
+        layer: usize,
+    }
+
+    impl ChallengeSource<Fq> for DroppedPhase {
+        fn append(&mut self, preimage: &[u8]) {
+            self.transcript.append(preimage)
+        }
+
+        fn get_random_challenge(&mut self) -> Fq {
+            self.transcript.get_random_challenge()
+        }
+
+        fn absorb_phase(&mut self, phase: &str, index: usize) {
+            if index != self.layer {
+                self.transcript.absorb_phase(phase, index)
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_fails_without_a_layer_phase_marker() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        for layer in 0..circuit.depth() {
+            let proof = prove(&mut circuit, &inputs);
+            let mut transcript = DroppedPhase {
+                transcript: Transcript::new(),
+                layer,
+            };
+
+            assert!(matches!(
+                verify_interactive(proof, circuit.clone(), &inputs, &mut transcript),
+                Err(GkrError::SumCheckFailed { layer: failed })
+                    | Err(GkrError::ClaimMismatch { layer: failed }) if failed == layer
+            ));
+        }
+
+        let proof = prove(&mut circuit, &inputs);
+        let mut transcript = DroppedPhase {
+            transcript: Transcript::new(),
+            layer: circuit.depth(),
+        };
+        assert_eq!(
+            verify_interactive(proof, circuit, &inputs, &mut transcript),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_derive_challenges_matches_verify() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let proof = prove(&mut circuit, &inputs);
+
+        let derived = proof.derive_challenges(&circuit).unwrap();
+        let verified =
+            verify_with_challenges(prove(&mut circuit, &inputs), circuit.clone(), &inputs).unwrap();
+
+        assert_eq!(derived.len(), verified.layer_challenges.len());
+
+        for (i, layer) in derived.iter().enumerate() {
+            assert_eq!(layer.sum_check, verified.layer_challenges[i]);
+            assert_eq!(layer.alpha_beta, verified.alphas_betas.get(i).copied());
+        }
+
+        let mut missing_claim = proof;
+        missing_claim.claimed_evaluations.pop();
+        assert_eq!(
+            missing_claim.derive_challenges(&circuit),
+            Err(GkrError::MalformedProof { layer: 1 })
+        );
+
+        let mut missing_round = prove(&mut circuit, &inputs);
+        missing_round.proof_polynomials.pop();
+        assert_eq!(
+            missing_round.derive_challenges(&circuit),
+            Err(GkrError::MalformedProof { layer: 2 })
+        );
+    }
+
+    struct AlternatingLayers {
+        depth: usize,
+    }
+
+    impl LayerSource<Fq> for AlternatingLayers {
+        fn layer(&self, index: usize) -> Layer<Fq> {
+            let n_gates = 1 << (self.depth - 1 - index);
+            let gates = (0..n_gates)
+                .map(|gate| {
+                    let op = if gate % 2 == 0 {
+                        Operation::Add
+                    } else {
+                        Operation::Mul
+                    };
+
+                    Gate::new(Fq::from(0), Fq::from(0), op)
+                })
+                .collect();
+
+            Layer::new(gates)
+        }
+
+        fn depth(&self) -> usize {
+            self.depth
+        }
+    }
+
+    #[test]
+    fn test_prove_and_verify_lazy_layer_source() {
+        let circuit = Circuit::from_source(AlternatingLayers { depth: 4 });
+        let inputs: Vec<Fq> = (1..=16).map(Fq::from).collect();
+
+        let proof = prove_low_memory(&circuit, &inputs);
+
+        let mut materialized = Circuit::new(
+            (0..circuit.depth())
+                .map(|index| {
+                    circuit
+                        .layer(index)
+                        .gates
+                        .iter()
+                        .map(|gate| gate.op)
+                        .collect()
+                })
+                .collect(),
+        );
+
+        assert_eq!(proof, prove(&mut materialized, &inputs));
+        assert!(verify(proof, circuit, &inputs));
+    }
+
+    /// Records this crate's log messages per thread, so tests running in
+    /// parallel only see their own.
+    struct CapturingLogger;
+
+    thread_local! {
+        static CAPTURED_LOGS: std::cell::RefCell<Vec<String>> =
+            const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata
+                .target()
+                .starts_with(module_path!().split("::").next().unwrap())
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                CAPTURED_LOGS.with(|logs| logs.borrow_mut().push(record.args().to_string()));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// The log messages `f` emits on this thread.
+    fn capture_logs(f: impl FnOnce()) -> Vec<String> {
+        // Only one logger can ever be set; every later call finds it in place.
+        let _ = log::set_logger(&CapturingLogger);
+        log::set_max_level(log::LevelFilter::Warn);
+
+        CAPTURED_LOGS.with(|logs| logs.borrow_mut().clear());
+        f();
+
+        CAPTURED_LOGS.with(|logs| logs.take())
+    }
+
+    #[test]
+    fn test_verify_verbose_reports_claim_difference() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let mut proof = prove(&mut circuit, &inputs);
+
+        assert_eq!(verify_verbose(&proof, &circuit, &inputs), Ok(()));
+
+        proof.claimed_evaluations[1].0 += Fq::from(1);
+
+        let mut failure = None;
+        let logs = capture_logs(|| failure = verify_verbose(&proof, &circuit, &inputs).err());
+        let failure = failure.unwrap();
+        let difference = failure.claim_difference.unwrap();
+
+        assert_eq!(failure.error, GkrError::ClaimMismatch { layer: 1 });
+        assert_ne!(difference, Fq::from(0));
+
+        assert!(logs.contains(&format!(
+            "claim mismatch at layer 1: claim - final_claimed_sum = {difference}"
+        )));
+    }
+
+    #[test]
+    fn test_verify_with_output_claims() {
+        let (mut circuit, inputs) = depth_4_circuit_and_inputs();
+        let outputs = circuit.evaluate_layer(&inputs, 3);
+
+        let proof = prove(&mut circuit, &inputs);
+        assert_eq!(
+            verify_with_output_claims(proof, circuit.clone(), &inputs, None),
+            Ok(())
+        );
+
+        let proof = prove(&mut circuit, &inputs);
+        assert_eq!(
+            verify_with_output_claims(proof, circuit.clone(), &inputs, Some(&outputs)),
+            Ok(())
+        );
+
+        let wrong = vec![outputs[0] + Fq::from(1)];
+        let proof = prove(&mut circuit, &inputs);
+        assert_eq!(
+            verify_with_output_claims(proof, circuit.clone(), &inputs, Some(&wrong)),
+            Err(GkrError::OutputClaimMismatch { index: 0 })
+        );
+
+        let too_many = vec![outputs[0], Fq::from(0)];
+        let proof = prove(&mut circuit, &inputs);
+        assert_eq!(
+            verify_with_output_claims(proof, circuit, &inputs, Some(&too_many)),
+            Err(GkrError::OutputClaimMismatch { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_output_assertion() {
+        let structure = vec![vec![Operation::Mul, Operation::Add]];
+        let mut circuit = Circuit::<Fq>::new(structure);
+        let inputs: Vec<Fq> = (1..=4).map(Fq::from).collect();
+        let expected = vec![Fq::from(2), Fq::from(7)];
+
+        let proof = prove_output_assertion(&mut circuit, &inputs, &expected);
+        assert_eq!(
+            verify_output_assertion(proof, circuit.clone(), &inputs, &expected),
+            Ok(())
+        );
+
+        let wrong = vec![Fq::from(2), Fq::from(8)];
+        let proof = prove_output_assertion(&mut circuit, &inputs, &expected);
+        assert_eq!(
+            verify_output_assertion(proof, circuit.clone(), &inputs, &wrong),
+            Err(GkrError::OutputClaimMismatch { index: 1 })
+        );
+
+        let proof = prove_output_assertion(&mut circuit, &inputs, &wrong);
+        assert_eq!(
+            verify_output_assertion(proof, circuit.clone(), &inputs, &wrong),
+            Err(GkrError::OutputClaimMismatch { index: 1 })
+        );
+
+        let proof = prove(&mut circuit, &inputs);
+        assert!(verify_output_assertion(proof, circuit, &inputs, &expected).is_err());
+    }
+
+    #[test]
+    fn test_verify_rejects_non_canonical_elements() {
+        use ark_ff::{BigInteger, Fp};
+
+        // `from_bigint` refuses values past the modulus, so the element is
+        // built from its raw Montgomery form instead: `x + p` for some
+        // canonical `x`.
+        let non_canonical = |x: Fq| {
+            let mut repr = x.0;
+            repr.add_with_carry(&Fq::MODULUS);
+
+            Fp::new_unchecked(repr)
+        };
+        assert_eq!(Fq::from_bigint(Fq::MODULUS), None);
+
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.claimed_evaluations[1].0 = non_canonical(proof.claimed_evaluations[1].0);
+        assert_eq!(
+            verify_in_transcript(proof, circuit.clone(), &inputs, &mut Transcript::new()),
+            Err(GkrError::NonCanonicalElement { layer: 1 })
+        );
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.output_poly.evaluation[0] = non_canonical(proof.output_poly.evaluation[0]);
+        assert_eq!(find_first_divergence(&proof, &circuit, &inputs), Some(0));
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.proof_polynomials[2][0][1] = non_canonical(proof.proof_polynomials[2][0][1]);
+        assert_eq!(
+            verify_parallel(&proof, &circuit, &inputs),
+            Err(GkrError::NonCanonicalElement { layer: 2 })
+        );
+    }
+
+    #[test]
+    fn test_prove_and_verify_from_layer() {
+        let (mut circuit, inputs) = depth_4_circuit_and_inputs();
+        let full = prove(&mut circuit, &inputs);
+        let trace = circuit.evaluate(&inputs);
+
+        for start_layer in 1..=circuit.depth() {
+            let intermediate = trace.layer_below(start_layer - 1);
+            let top = prove_from_layer(&circuit, start_layer, intermediate).unwrap();
+
+            assert_eq!(top.output_poly, full.output_poly);
+            assert_eq!(top.proof_polynomials, full.proof_polynomials[..start_layer]);
+            assert_eq!(
+                top.claimed_evaluations,
+                full.claimed_evaluations[..start_layer - 1]
+            );
+
+            let mut wrong_intermediate = intermediate.to_vec();
+            wrong_intermediate[0] += Fq::from(1);
+
+            let top_again = prove_from_layer(&circuit, start_layer, intermediate).unwrap();
+            assert_eq!(
+                verify_from_layer(top, &circuit, start_layer, intermediate),
+                Ok(())
+            );
+            assert!(
+                verify_from_layer(top_again, &circuit, start_layer, &wrong_intermediate).is_err()
+            );
+        }
+
+        assert_eq!(
+            prove_from_layer(&circuit, circuit.depth(), &inputs),
+            Ok(prove(&mut circuit, &inputs))
+        );
+        assert_eq!(
+            prove_from_layer(&circuit, 0, &inputs),
+            Err(GkrError::InvalidSplit { start_layer: 0 })
+        );
+    }
+
+    #[test]
+    fn test_proofs_of_both_halves_compose_at_the_split() {
+        let (mut circuit, inputs) = depth_4_circuit_and_inputs();
+        circuit.add_skip_connection(2, 1).unwrap();
+        let trace = circuit.evaluate(&inputs);
+        let intermediate = trace.layer_below(0);
+
+        let mut bottom = circuit.bottom_layers(3).unwrap();
+        let bottom_proof = prove(&mut bottom, &inputs);
+        assert_eq!(bottom_proof.output_poly.evaluation, intermediate);
+        assert!(verify(bottom_proof, bottom, &inputs));
+
+        let top = prove_from_layer(&circuit, 1, intermediate).unwrap();
+        assert_eq!(verify_from_layer(top, &circuit, 1, intermediate), Ok(()));
+        assert!(verify(
+            prove(&mut circuit, &inputs),
+            circuit.clone(),
+            &inputs
+        ));
+
+        // The lowest of the top two layers reads its skip connection from
+        // below the split, where the top half has no wires.
+        assert_eq!(
+            prove_from_layer(&circuit, 2, trace.layer_below(1)),
+            Err(GkrError::InvalidSplit { start_layer: 2 })
+        );
+        assert_eq!(
+            verify_from_layer(
+                prove(&mut circuit, &inputs),
+                &circuit,
+                2,
+                trace.layer_below(1)
+            ),
+            Err(GkrError::InvalidSplit { start_layer: 2 })
+        );
+    }
+
+    #[test]
+    fn test_check_challenge_split() {
+        let mut circuit = Circuit::<Fq>::new(vec![
+            vec![Operation::Mul; 4],
+            vec![Operation::Add; 2],
+            vec![Operation::Add],
+        ]);
+        circuit.add_skip_connection(1, 0).unwrap();
+
+        // Two gates read input wires of 2 bits, plus 2 bits for the skip
+        // connection, so splitting by the gate count alone would be wrong.
+        let layer = circuit.layer(1);
+        let challenges: Vec<Fq> = (1..=8).map(Fq::from).collect();
+
+        assert_eq!(check_challenge_split(&layer, 1, &challenges), Ok(()));
+        assert_eq!(
+            check_challenge_split(&layer, 1, &challenges[..4]),
+            Err(GkrError::ChallengeSplitMismatch {
+                layer: 1,
+                expected: 8,
+                actual: 4
+            })
+        );
+
+        let (r_b, r_c) = claim_points(&layer, &challenges);
+        assert_eq!((r_b, r_c), (&challenges[2..4], &challenges[6..]));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "do not split into two input wires")]
+    fn test_claim_points_rejects_a_wrong_split() {
+        let (circuit, _) = sample_circuit_and_inputs();
+        let challenges: Vec<Fq> = (1..=6).map(Fq::from).collect();
+
+        let _ = claim_points(&circuit.layer(1), &challenges);
+    }
+
+    #[test]
+    fn test_verify_structure_only_checks_shape() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let proof = prove(&mut circuit, &inputs);
+
+        assert_eq!(
+            verify_structure(&proof, &circuit, FbcConstruction::default()),
+            Ok(())
+        );
+
+        let mut tampered = prove(&mut circuit, &inputs);
+        tampered.proof_polynomials[1][0][0] += Fq::from(1);
+        tampered.claimed_evaluations[0].1 += Fq::from(1);
+
+        assert_eq!(
+            verify_structure(&tampered, &circuit, FbcConstruction::default()),
+            Ok(())
+        );
+        assert!(!verify(tampered, circuit.clone(), &inputs));
+
+        let mut short_output = prove(&mut circuit, &inputs);
+        short_output.output_poly = MultilinearPoly::new(vec![Fq::from(1)]);
+        assert_eq!(
+            verify_structure(&short_output, &circuit, FbcConstruction::default()),
+            Err(GkrError::MalformedProof { layer: 0 })
+        );
+
+        let mut missing_round = prove(&mut circuit, &inputs);
+        missing_round.proof_polynomials[2].pop();
+        assert_eq!(
+            verify_structure(&missing_round, &circuit, FbcConstruction::default()),
+            Err(GkrError::MalformedProof { layer: 2 })
+        );
+
+        let mut missing_claim = prove(&mut circuit, &inputs);
+        missing_claim.claimed_evaluations.pop();
+        assert_eq!(
+            verify_structure(&missing_claim, &circuit, FbcConstruction::default()),
+            Err(GkrError::MalformedProof { layer: 1 })
+        );
+    }
+
+    #[test]
+    fn test_verify_structure_takes_the_construction() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let config = GkrConfig {
+            fbc_construction: FbcConstruction::SingleProduct,
+            ..GkrConfig::default()
+        };
+        let proof = prove_with_config(&mut circuit, &inputs, &config).unwrap();
+
+        assert_eq!(
+            verify_structure(&proof, &circuit, FbcConstruction::SingleProduct),
+            Ok(())
+        );
+        assert_eq!(
+            verify_structure(&proof, &circuit, FbcConstruction::SumOfProducts),
+            Err(GkrError::MalformedProof { layer: 0 })
+        );
+
+        assert_eq!(
+            verify_structure(&proof, &Circuit::new(vec![]), FbcConstruction::default()),
+            Err(GkrError::MalformedProof { layer: 0 })
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_a_wide_output_poly() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.output_poly = MultilinearPoly::new(vec![Fq::from(1); 4]);
+
+        assert_eq!(
+            verify_parallel(&proof, &circuit, &inputs),
+            Err(GkrError::MalformedProof { layer: 0 })
+        );
+        assert_eq!(
+            verify_in_transcript(proof, circuit, &inputs, &mut Transcript::new()),
+            Err(GkrError::MalformedProof { layer: 0 })
+        );
+    }
+
+    #[test]
+    fn test_verify_audit_records_every_claim_check() {
+        let (mut circuit, inputs) = depth_4_circuit_and_inputs();
+        let proof = prove(&mut circuit, &inputs);
+
+        let trail = verify_audit(&proof, &circuit, &inputs);
+
+        assert_eq!(trail.result, Ok(()));
+        assert_eq!(trail.checks.len(), circuit.depth());
+        assert_eq!(trail.checks[0].claimed_sum, proof.output_claim().0);
+
+        let input_check = trail.checks.last().unwrap();
+        assert_eq!(input_check.layer, circuit.depth() - 1);
+        assert_eq!(input_check.expected_claim, input_check.final_claimed_sum);
+
+        let challenges = verify_with_challenges(proof, circuit.clone(), &inputs).unwrap();
+        let (alpha, beta) = *challenges.alphas_betas.last().unwrap();
+        let (o_1, o_2) = prove(&mut circuit, &inputs).claimed_evaluations[2];
+        assert_eq!(input_check.claimed_sum, alpha * o_1 + beta * o_2);
+
+        let mut proof = prove(&mut circuit, &inputs);
+        proof.claimed_evaluations[1].0 += Fq::from(1);
+
+        let trail = verify_audit(&proof, &circuit, &inputs);
+
+        assert_eq!(trail.result, Err(GkrError::ClaimMismatch { layer: 1 }));
+        assert_eq!(trail.checks.len(), 2);
+        assert_ne!(
+            trail.checks[1].expected_claim,
+            trail.checks[1].final_claimed_sum
+        );
+    }
+
+    #[test]
+    fn test_expected_input_claim_matches_the_verifier() {
+        let (mut circuit, inputs) = depth_4_circuit_and_inputs();
+        let proof = prove(&mut circuit, &inputs);
+
+        let trail = verify_audit(&proof, &circuit, &inputs);
+        let challenges = verify_with_challenges(proof, circuit.clone(), &inputs).unwrap();
+
+        let above = &challenges.layer_challenges[circuit.depth() - 2];
+        let (r_1, r_2) = above.split_at(above.len() / 2);
+        let (alpha, beta) = *challenges.alphas_betas.last().unwrap();
+        let final_point = challenges.layer_challenges.last().unwrap();
+
+        let expected = expected_input_claim(&circuit, &inputs, r_1, r_2, alpha, beta, final_point);
+
+        assert_eq!(trail.checks.last().unwrap().expected_claim, expected);
+        assert_ne!(
+            expected_input_claim(&circuit, &inputs, r_2, r_1, alpha, beta, final_point),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_find_first_divergence() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let mut proof = prove(&mut circuit, &inputs);
+
+        assert_eq!(find_first_divergence(&proof, &circuit, &inputs), None);
+
+        proof.claimed_evaluations[1].0 += Fq::from(1);
+
+        assert_eq!(find_first_divergence(&proof, &circuit, &inputs), Some(1));
+    }
+
+    #[test]
+    fn test_verify_rejects_mis_sized_inputs() {
+        let circuit_structure: Vec<Vec<Operation>> =
+            vec![vec![Operation::Mul, Operation::Mul], vec![Operation::Add]];
+        let inputs: Vec<Fq> = vec![Fq::from(1), Fq::from(2), Fq::from(3), Fq::from(4)];
+
+        let mut circuit = Circuit::new(circuit_structure);
+        let proof = prove(&mut circuit, &inputs);
+
+        assert_eq!(
+            verify_in_transcript(proof, circuit, &inputs[..3], &mut Transcript::new()),
+            Err(GkrError::InputLengthMismatch {
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_prove_and_verify_fixtures() {
+        for (mut circuit, inputs) in [
+            depth_1_circuit_and_inputs(),
+            sample_circuit_and_inputs(),
+            depth_4_circuit_and_inputs(),
+        ] {
+            let proof = prove(&mut circuit, &inputs);
+
+            assert!(verify(proof, circuit, &inputs));
+        }
+    }
+
+    #[test]
+    fn test_prove_and_verify_single_gate_circuit() {
+        let mut circuit = Circuit::<Fq>::new(vec![vec![Operation::Add]]);
+        let inputs = vec![Fq::from(3), Fq::from(4)];
+
+        let proof = prove(&mut circuit, &inputs);
+
+        // The single output is padded to a one-variable output poly, so the
+        // output challenge is drawn as for any other circuit.
+        assert_eq!(proof.output_poly.evaluation, vec![Fq::from(7), Fq::from(0)]);
+        assert_eq!(proof.proof_polynomials.len(), 1);
+        assert_eq!(proof.proof_polynomials[0].len(), 2);
+        assert!(proof.claimed_evaluations.is_empty());
+
+        let challenges = verify_with_challenges(proof, circuit.clone(), &inputs).unwrap();
+        assert_eq!(challenges.layer_challenges.len(), 1);
+        assert!(challenges.alphas_betas.is_empty());
+
+        let proof = prove(&mut circuit, &inputs);
+        assert_eq!(verify_parallel(&proof, &circuit, &inputs), Ok(()));
+        assert!(!verify(proof, circuit.clone(), &[Fq::from(3), Fq::from(5)]));
+
+        let padded = prove_padding(&mut circuit, &[Fq::from(3)]).unwrap();
+        assert!(verify(padded, circuit, &[Fq::from(3), Fq::from(0)]));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_output_poly() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+
+        for idx in 0..2 {
+            let mut proof = prove(&mut circuit, &inputs);
+            proof.output_poly.evaluation[idx] += Fq::from(1);
+
+            assert!(!verify(proof, circuit.clone(), &inputs));
+        }
     }
 
     #[test]
@@ -476,10 +4383,11 @@ mod test {
                 ],
             ],
             claimed_evaluations: vec![(Fq::from(10), Fq::from(5))],
+            skip_evaluations: Vec::new(),
         };
 
         let is_verified = verify(invalid_proof, circuit, &inputs);
 
-        assert_eq!(is_verified, false);
+        assert!(!is_verified);
     }
 }
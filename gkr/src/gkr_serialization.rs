@@ -0,0 +1,505 @@
+use crate::gkr_protocol::Proof;
+
+use ark_ff::PrimeField;
+use multilinear_polynomial::multilinear_polynomial_evaluation::MultilinearPoly;
+use sha3::{Digest, Keccak256};
+use std::io::{self, Read, Write};
+
+/// v1 stored every sum-check round polynomial as exactly three evaluations.
+/// v2 prefixes each round polynomial with its length.
+/// v3 appends the skip connection evaluations.
+pub const PROOF_FORMAT_VERSION: u16 = 3;
+
+const V1_ROUND_POLY_LEN: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SerializationError {
+    UnexpectedEnd,
+    UnknownVersion(u16),
+    InvalidFieldElement,
+    InvalidOutputPoly,
+    TrailingBytes,
+    /// Reading a framed proof from a stream failed for a reason other than
+    /// the stream ending early.
+    Io(io::ErrorKind),
+}
+
+/// Bytes of field elements attributable to each part of a proof, excluding
+/// the serialization framing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofSizeBreakdown {
+    pub output_poly: usize,
+    pub layers: Vec<usize>,
+    pub claimed_evaluations: usize,
+    pub skip_evaluations: usize,
+}
+
+impl ProofSizeBreakdown {
+    pub fn total(&self) -> usize {
+        self.output_poly
+            + self.layers.iter().sum::<usize>()
+            + self.claimed_evaluations
+            + self.skip_evaluations
+    }
+}
+
+impl<F: PrimeField> Proof<F> {
+    pub fn size_in_field_elements(&self) -> usize {
+        let round_polys: usize = self
+            .proof_polynomials
+            .iter()
+            .flatten()
+            .map(|round_poly| round_poly.len())
+            .sum();
+
+        self.output_poly.evaluation.len()
+            + round_polys
+            + self.claimed_evaluations.len() * 2
+            + self.skip_evaluations.len()
+    }
+
+    pub fn size_breakdown(&self) -> ProofSizeBreakdown {
+        let element_size = F::zero().compressed_size();
+
+        ProofSizeBreakdown {
+            output_poly: self.output_poly.evaluation.len() * element_size,
+            layers: self
+                .proof_polynomials
+                .iter()
+                .map(|layer_polys| {
+                    layer_polys.iter().map(|poly| poly.len()).sum::<usize>() * element_size
+                })
+                .collect(),
+            claimed_evaluations: self.claimed_evaluations.len() * 2 * element_size,
+            skip_evaluations: self.skip_evaluations.len() * element_size,
+        }
+    }
+
+    /// Share of the proof's field-element bytes taken by the output poly, as
+    /// counted by `size_breakdown`. It grows with the output width, which is
+    /// when committing to the output poly instead of sending it pays off.
+    pub fn output_fraction(&self) -> f64 {
+        let breakdown = self.size_breakdown();
+
+        breakdown.output_poly as f64 / breakdown.total() as f64
+    }
+
+    /// Keccak hash of the output poly, encoded as `serialize` writes it. It
+    /// only depends on the circuit's outputs, not on the transcript, so
+    /// proofs of the same outputs can share one cached copy of the poly.
+    pub fn output_commitment(&self) -> [u8; 32] {
+        let mut bytes = Vec::new();
+        write_field_elements(&mut bytes, &self.output_poly.evaluation);
+
+        Keccak256::digest(bytes).into()
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&PROOF_FORMAT_VERSION.to_le_bytes());
+
+        write_field_elements(&mut bytes, &self.output_poly.evaluation);
+
+        write_len(&mut bytes, self.proof_polynomials.len());
+        for layer_polys in &self.proof_polynomials {
+            write_len(&mut bytes, layer_polys.len());
+
+            for round_poly in layer_polys {
+                write_field_elements(&mut bytes, round_poly);
+            }
+        }
+
+        write_len(&mut bytes, self.claimed_evaluations.len());
+        for (o_1, o_2) in &self.claimed_evaluations {
+            write_field_element(&mut bytes, o_1);
+            write_field_element(&mut bytes, o_2);
+        }
+
+        write_field_elements(&mut bytes, &self.skip_evaluations);
+
+        bytes
+    }
+
+    pub fn deserialize_versioned(bytes: &[u8]) -> Result<Self, SerializationError> {
+        let mut reader = Reader { bytes };
+
+        let version = reader.read_u16()?;
+        let round_poly_len = match version {
+            1 => Some(V1_ROUND_POLY_LEN),
+            2 | 3 => None,
+            _ => return Err(SerializationError::UnknownVersion(version)),
+        };
+
+        let output_evaluations = reader.read_field_elements()?;
+        if output_evaluations.is_empty() || !output_evaluations.len().is_power_of_two() {
+            return Err(SerializationError::InvalidOutputPoly);
+        }
+
+        let num_layers = reader.read_len()?;
+        let mut proof_polynomials = Vec::with_capacity(reader.capacity(num_layers, 4));
+        for _ in 0..num_layers {
+            let num_rounds = reader.read_len()?;
+            let mut layer_polys = Vec::with_capacity(reader.capacity(num_rounds, 4));
+
+            for _ in 0..num_rounds {
+                let round_poly = match round_poly_len {
+                    Some(len) => (0..len)
+                        .map(|_| reader.read_field_element())
+                        .collect::<Result<Vec<F>, _>>()?,
+                    None => reader.read_field_elements()?,
+                };
+                layer_polys.push(round_poly);
+            }
+
+            proof_polynomials.push(layer_polys);
+        }
+
+        let num_claims = reader.read_len()?;
+        let mut claimed_evaluations =
+            Vec::with_capacity(reader.capacity(num_claims, 2 * F::zero().compressed_size()));
+        for _ in 0..num_claims {
+            let o_1 = reader.read_field_element()?;
+            let o_2 = reader.read_field_element()?;
+            claimed_evaluations.push((o_1, o_2));
+        }
+
+        let skip_evaluations = match version {
+            3 => reader.read_field_elements()?,
+            _ => Vec::new(),
+        };
+
+        if !reader.bytes.is_empty() {
+            return Err(SerializationError::TrailingBytes);
+        }
+
+        Ok(Proof {
+            output_poly: MultilinearPoly::new(output_evaluations),
+            proof_polynomials,
+            claimed_evaluations,
+            skip_evaluations,
+        })
+    }
+}
+
+impl<F: PrimeField> Proof<F> {
+    /// Writes the serialized proof behind a little-endian `u32` byte length,
+    /// so several proofs can follow each other on one stream. Fails with
+    /// `io::ErrorKind::InvalidInput`, writing nothing, when the proof takes
+    /// more bytes than the prefix can count.
+    pub fn write_framed(&self, w: &mut impl Write) -> io::Result<()> {
+        let bytes = self.serialize();
+
+        w.write_all(&frame_prefix(bytes.len())?)?;
+        w.write_all(&bytes)
+    }
+
+    /// Reads exactly one proof written by `write_framed`, leaving the rest of
+    /// the stream unread.
+    pub fn read_framed(r: &mut impl Read) -> Result<Self, SerializationError> {
+        let mut prefix = [0; 4];
+        r.read_exact(&mut prefix).map_err(stream_error)?;
+        let len = u32::from_le_bytes(prefix) as usize;
+
+        let mut bytes = Vec::new();
+        r.take(len as u64)
+            .read_to_end(&mut bytes)
+            .map_err(stream_error)?;
+
+        if bytes.len() != len {
+            return Err(SerializationError::UnexpectedEnd);
+        }
+
+        Self::deserialize_versioned(&bytes)
+    }
+}
+
+fn frame_prefix(len: usize) -> io::Result<[u8; 4]> {
+    match u32::try_from(len) {
+        Ok(len) => Ok(len.to_le_bytes()),
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("a {len} byte proof does not fit a u32 frame length"),
+        )),
+    }
+}
+
+fn stream_error(error: io::Error) -> SerializationError {
+    match error.kind() {
+        io::ErrorKind::UnexpectedEof => SerializationError::UnexpectedEnd,
+        kind => SerializationError::Io(kind),
+    }
+}
+
+fn write_len(bytes: &mut Vec<u8>, len: usize) {
+    bytes.extend_from_slice(&(len as u32).to_le_bytes());
+}
+
+fn write_field_element<F: PrimeField>(bytes: &mut Vec<u8>, value: &F) {
+    value
+        .serialize_compressed(bytes)
+        .expect("writing to a Vec cannot fail");
+}
+
+fn write_field_elements<F: PrimeField>(bytes: &mut Vec<u8>, values: &[F]) {
+    write_len(bytes, values.len());
+
+    for value in values {
+        write_field_element(bytes, value);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Reader<'_> {
+    /// `len` capped at the number of items of at least `min_size` bytes each
+    /// the remaining bytes can hold, so a length prefix read from untrusted
+    /// bytes cannot make the reader preallocate more than the input backs.
+    fn capacity(&self, len: usize, min_size: usize) -> usize {
+        len.min(self.bytes.len() / min_size)
+    }
+
+    fn take(&mut self, n: usize) -> Result<&[u8], SerializationError> {
+        if self.bytes.len() < n {
+            return Err(SerializationError::UnexpectedEnd);
+        }
+
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+
+        Ok(head)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, SerializationError> {
+        let bytes = self.take(2)?;
+
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_len(&mut self) -> Result<usize, SerializationError> {
+        let bytes = self.take(4)?;
+
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    }
+
+    fn read_field_element<F: PrimeField>(&mut self) -> Result<F, SerializationError> {
+        let bytes = self.take(F::zero().compressed_size())?;
+
+        F::deserialize_compressed(bytes).map_err(|_| SerializationError::InvalidFieldElement)
+    }
+
+    fn read_field_elements<F: PrimeField>(&mut self) -> Result<Vec<F>, SerializationError> {
+        let len = self.read_len()?;
+
+        (0..len).map(|_| self.read_field_element()).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        gkr_circuit::{Circuit, Operation},
+        gkr_protocol::{prove, prove_deterministic, verify},
+        test_util::sample_circuit_and_inputs,
+    };
+    use ark_bn254::Fq;
+    use fiat_shamir::fiat_shamir_transcript::field_vec_to_bytes;
+
+    fn sample_proof() -> (Proof<Fq>, Circuit<Fq>, Vec<Fq>) {
+        let circuit_structure: Vec<Vec<Operation>> =
+            vec![vec![Operation::Mul, Operation::Mul], vec![Operation::Add]];
+
+        let inputs: Vec<Fq> = vec![Fq::from(1), Fq::from(2), Fq::from(3), Fq::from(4)];
+
+        let mut circuit = Circuit::new(circuit_structure);
+        let proof = prove(&mut circuit, &inputs);
+
+        (proof, circuit, inputs)
+    }
+
+    fn v1_fixture(proof: &Proof<Fq>) -> Vec<u8> {
+        let mut bytes = 1u16.to_le_bytes().to_vec();
+
+        bytes.extend_from_slice(&(proof.output_poly.evaluation.len() as u32).to_le_bytes());
+        bytes.extend(field_vec_to_bytes(&proof.output_poly.evaluation));
+
+        bytes.extend_from_slice(&(proof.proof_polynomials.len() as u32).to_le_bytes());
+        for layer_polys in &proof.proof_polynomials {
+            bytes.extend_from_slice(&(layer_polys.len() as u32).to_le_bytes());
+            for round_poly in layer_polys {
+                bytes.extend(field_vec_to_bytes(round_poly));
+            }
+        }
+
+        bytes.extend_from_slice(&(proof.claimed_evaluations.len() as u32).to_le_bytes());
+        for (o_1, o_2) in &proof.claimed_evaluations {
+            bytes.extend(field_vec_to_bytes(&[*o_1, *o_2]));
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn it_rejects_length_prefixes_past_the_input() {
+        let (proof, _, _) = sample_proof();
+        let mut header = PROOF_FORMAT_VERSION.to_le_bytes().to_vec();
+        write_field_elements(&mut header, &proof.output_poly.evaluation);
+
+        let mut huge_layers = header.clone();
+        huge_layers.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            Proof::<Fq>::deserialize_versioned(&huge_layers),
+            Err(SerializationError::UnexpectedEnd)
+        );
+
+        let mut huge_rounds = header.clone();
+        write_len(&mut huge_rounds, 1);
+        huge_rounds.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            Proof::<Fq>::deserialize_versioned(&huge_rounds),
+            Err(SerializationError::UnexpectedEnd)
+        );
+
+        let mut huge_claims = header;
+        write_len(&mut huge_claims, 0);
+        huge_claims.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(
+            Proof::<Fq>::deserialize_versioned(&huge_claims),
+            Err(SerializationError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn it_commits_to_the_output_poly_independently_of_the_transcript() {
+        let (proof, mut circuit, inputs) = sample_proof();
+
+        assert_eq!(
+            prove(&mut circuit, &inputs).output_commitment(),
+            proof.output_commitment()
+        );
+
+        let reproved = prove_deterministic(&mut circuit, &inputs, b"seed");
+        assert_ne!(reproved.serialize(), proof.serialize());
+        assert_eq!(reproved.output_commitment(), proof.output_commitment());
+
+        let other_inputs: Vec<Fq> = (2..=5).map(Fq::from).collect();
+        assert_ne!(
+            prove(&mut circuit, &other_inputs).output_commitment(),
+            proof.output_commitment()
+        );
+    }
+
+    #[test]
+    fn it_reads_framed_proofs_back_one_at_a_time() {
+        let (_, mut circuit, _) = sample_proof();
+        let inputs: Vec<Vec<Fq>> = (1..=3)
+            .map(|offset| (offset..offset + 4).map(Fq::from).collect())
+            .collect();
+
+        let mut stream = Vec::new();
+        for inputs in &inputs {
+            prove(&mut circuit, inputs)
+                .write_framed(&mut stream)
+                .unwrap();
+        }
+
+        let mut reader = stream.as_slice();
+        for inputs in &inputs {
+            let proof = Proof::<Fq>::read_framed(&mut reader).unwrap();
+
+            assert_eq!(proof, prove(&mut circuit, inputs));
+            assert!(verify(proof, circuit.clone(), inputs));
+        }
+
+        assert!(reader.is_empty());
+        assert_eq!(
+            Proof::<Fq>::read_framed(&mut reader),
+            Err(SerializationError::UnexpectedEnd)
+        );
+
+        assert_eq!(frame_prefix(5).unwrap(), [5, 0, 0, 0]);
+        assert_eq!(
+            frame_prefix(u32::MAX as usize + 1).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        let mut truncated = &stream[..stream.len() / 3];
+        assert!(Proof::<Fq>::read_framed(&mut truncated).is_ok());
+        assert_eq!(
+            Proof::<Fq>::read_framed(&mut truncated),
+            Err(SerializationError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn it_round_trips_a_proof() {
+        let (proof, circuit, inputs) = sample_proof();
+
+        let bytes = proof.serialize();
+
+        assert_eq!(&bytes[..2], &PROOF_FORMAT_VERSION.to_le_bytes());
+
+        let deserialized = Proof::<Fq>::deserialize_versioned(&bytes).unwrap();
+
+        assert_eq!(deserialized, proof);
+        assert!(verify(deserialized, circuit, &inputs));
+    }
+
+    #[test]
+    fn it_deserializes_a_v1_proof() {
+        let (proof, circuit, inputs) = sample_proof();
+
+        let fixture = v1_fixture(&proof);
+
+        let deserialized = Proof::<Fq>::deserialize_versioned(&fixture).unwrap();
+
+        assert_eq!(deserialized, proof);
+        assert!(verify(deserialized, circuit, &inputs));
+    }
+
+    #[test]
+    fn it_breaks_down_proof_size() {
+        let (mut circuit, inputs) = sample_circuit_and_inputs();
+        let proof = prove(&mut circuit, &inputs);
+
+        let breakdown = proof.size_breakdown();
+
+        assert_eq!(breakdown.output_poly, 2 * 32);
+        assert_eq!(breakdown.layers, vec![2 * 3 * 32, 4 * 3 * 32, 6 * 3 * 32]);
+        assert_eq!(breakdown.claimed_evaluations, 2 * 2 * 32);
+        assert_eq!(breakdown.total(), proof.size_in_field_elements() * 32);
+    }
+
+    #[test]
+    fn it_reports_a_larger_output_fraction_for_wide_outputs() {
+        let (mut narrow, inputs) = sample_circuit_and_inputs();
+        let mut wide = Circuit::new(vec![vec![Operation::Mul; 4], vec![Operation::Add; 2]]);
+
+        let narrow_fraction = prove(&mut narrow, &inputs).output_fraction();
+        let wide_fraction = prove(&mut wide, &inputs).output_fraction();
+
+        assert_eq!(narrow_fraction, 64.0 / (64.0 + 1152.0 + 128.0));
+        assert!(wide_fraction > narrow_fraction);
+        assert!(wide_fraction < 1.0);
+    }
+
+    #[test]
+    fn it_rejects_unknown_versions_and_truncated_bytes() {
+        let (proof, _, _) = sample_proof();
+        let mut bytes = proof.serialize();
+
+        assert_eq!(
+            Proof::<Fq>::deserialize_versioned(&bytes[..bytes.len() - 1]),
+            Err(SerializationError::UnexpectedEnd)
+        );
+
+        bytes[..2].copy_from_slice(&7u16.to_le_bytes());
+
+        assert_eq!(
+            Proof::<Fq>::deserialize_versioned(&bytes),
+            Err(SerializationError::UnknownVersion(7))
+        );
+    }
+}
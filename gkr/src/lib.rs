@@ -1,2 +1,10 @@
+pub mod gkr_challenges;
 pub mod gkr_circuit;
+pub mod gkr_config;
+pub mod gkr_layering;
+pub mod gkr_merkle;
+pub mod gkr_preprocessing;
 pub mod gkr_protocol;
+pub mod gkr_serialization;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
@@ -0,0 +1,79 @@
+use crate::gkr_circuit::{Circuit, GateFn, Operation};
+
+use ark_bn254::Fq;
+
+/// The canonical three-layer fixture: four muls, two adds and one add over
+/// eight inputs, with output 27.
+pub fn sample_circuit_and_inputs() -> (Circuit<Fq>, Vec<Fq>) {
+    let circuit_structure: Vec<Vec<Operation>> = vec![
+        vec![
+            Operation::Mul,
+            Operation::Mul,
+            Operation::Mul,
+            Operation::Mul,
+        ],
+        vec![Operation::Add, Operation::Add],
+        vec![Operation::Add],
+    ];
+
+    let inputs: Vec<Fq> = vec![
+        Fq::from(5),
+        Fq::from(2),
+        Fq::from(2),
+        Fq::from(4),
+        Fq::from(10),
+        Fq::from(0),
+        Fq::from(3),
+        Fq::from(3),
+    ];
+
+    (Circuit::new(circuit_structure), inputs)
+}
+
+/// A single add gate over two inputs, with output 7.
+pub fn depth_1_circuit_and_inputs() -> (Circuit<Fq>, Vec<Fq>) {
+    let circuit_structure: Vec<Vec<Operation>> = vec![vec![Operation::Add]];
+
+    let inputs: Vec<Fq> = vec![Fq::from(3), Fq::from(4)];
+
+    (Circuit::new(circuit_structure), inputs)
+}
+
+/// Four layers with mixed add/mul gates over the inputs 1 to 16.
+pub fn depth_4_circuit_and_inputs() -> (Circuit<Fq>, Vec<Fq>) {
+    let circuit_structure: Vec<Vec<Operation>> = vec![
+        vec![Operation::Mul; 8],
+        vec![
+            Operation::Add,
+            Operation::Mul,
+            Operation::Add,
+            Operation::Mul,
+        ],
+        vec![Operation::Add, Operation::Mul],
+        vec![Operation::Add],
+    ];
+
+    let inputs: Vec<Fq> = (1..=16).map(Fq::from).collect();
+
+    (Circuit::new(circuit_structure), inputs)
+}
+
+/// A degree-2 custom gate computing `a * a + scale * b`.
+#[derive(Debug)]
+pub struct SquarePlus {
+    pub scale: u64,
+}
+
+impl GateFn<Fq> for SquarePlus {
+    fn apply(&self, a: Fq, b: Fq) -> Fq {
+        a * a + Fq::from(self.scale) * b
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn selector_contribution(&self) -> Vec<(Fq, usize, usize)> {
+        vec![(Fq::from(1), 2, 0), (Fq::from(self.scale), 0, 1)]
+    }
+}
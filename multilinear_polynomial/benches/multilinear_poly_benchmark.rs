@@ -1,7 +1,70 @@
 use ark_bn254::Fq;
+use ark_ff::PrimeField;
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use multilinear_polynomial::multilinear_polynomial_evaluation::MultilinearPoly;
 
+/// Elements per batch in the batched loops below, which split the field
+/// multiplications of a batch from their additions so they can overlap in
+/// the pipeline.
+const LANES: usize = 4;
+
+/// The fold of `MultilinearPoly::multi_partial_evaluate`.
+fn fold_halves_scalar<F: PrimeField>(buffer: &mut [F], len: usize, value: F) {
+    for i in 0..len {
+        let (a, b) = (buffer[i], buffer[i + len]);
+        buffer[i] = a + value * (b - a);
+    }
+}
+
+fn fold_halves_batched<F: PrimeField>(buffer: &mut [F], len: usize, value: F) {
+    let (low, high) = buffer[..2 * len].split_at_mut(len);
+    let mut low_batches = low.chunks_exact_mut(LANES);
+    let mut high_batches = high.chunks_exact(LANES);
+
+    for (low, high) in (&mut low_batches).zip(&mut high_batches) {
+        let mut steps = [F::zero(); LANES];
+
+        for ((step, b), a) in steps.iter_mut().zip(high).zip(low.iter()) {
+            *step = *b - a;
+        }
+        for step in steps.iter_mut() {
+            *step *= value;
+        }
+        for (a, step) in low.iter_mut().zip(steps) {
+            *a += step;
+        }
+    }
+
+    for (a, b) in low_batches
+        .into_remainder()
+        .iter_mut()
+        .zip(high_batches.remainder())
+    {
+        *a += value * (*b - *a);
+    }
+}
+
+/// One row of the mul table of `tensor_add_mul_polynomials` in the gkr crate.
+fn extend_scaled_scalar<F: PrimeField>(out: &mut Vec<F>, scalar: F, row: &[F]) {
+    out.extend(row.iter().map(|x| scalar * x));
+}
+
+fn extend_scaled_batched<F: PrimeField>(out: &mut Vec<F>, scalar: F, row: &[F]) {
+    let mut batches = row.chunks_exact(LANES);
+
+    for batch in &mut batches {
+        let mut products = [F::zero(); LANES];
+
+        for (product, x) in products.iter_mut().zip(batch) {
+            *product = scalar * x;
+        }
+
+        out.extend_from_slice(&products);
+    }
+
+    out.extend(batches.remainder().iter().map(|x| scalar * x));
+}
+
 pub fn criterion_benchmark(c: &mut Criterion) {
     // Set the number of variables; our polynomial will have 2^(num_vars) evaluations.
     let num_vars = 10;
@@ -26,6 +89,75 @@ pub fn criterion_benchmark(c: &mut Criterion) {
             black_box(result);
         })
     });
+
+    // Fix 9 of the variables, the size of a selector's b and c variables.
+    let challenges: Vec<Fq> = (0..9).map(|i| Fq::from(i as u64 + 2)).collect();
+
+    c.bench_function(
+        "MultilinearPoly multi_partial_evaluate per-variable polys",
+        |b| {
+            b.iter(|| {
+                let result = black_box(&challenges)
+                    .iter()
+                    .fold(poly.clone(), |acc, value| acc.partial_evaluate(0, value));
+                black_box(result);
+            })
+        },
+    );
+
+    c.bench_function("MultilinearPoly multi_partial_evaluate in place", |b| {
+        b.iter(|| {
+            let result = poly.multi_partial_evaluate(black_box(&challenges));
+            black_box(result);
+        })
+    });
+
+    // The scalar hot loops against the same loops in batches of `LANES`.
+    let table: Vec<Fq> = (0..1 << 16).map(|i| Fq::from(i as u64)).collect();
+    let half = table.len() / 2;
+
+    let mut scalar = table.clone();
+    let mut batched = table.clone();
+    fold_halves_scalar(&mut scalar, half, Fq::from(7));
+    fold_halves_batched(&mut batched, half, Fq::from(7));
+    assert_eq!(scalar, batched);
+
+    let (mut scalar, mut batched) = (Vec::new(), Vec::new());
+    extend_scaled_scalar(&mut scalar, Fq::from(7), &table);
+    extend_scaled_batched(&mut batched, Fq::from(7), &table);
+    assert_eq!(scalar, batched);
+
+    c.bench_function("fold_halves scalar", |b| {
+        b.iter(|| {
+            let mut buffer = table.clone();
+            fold_halves_scalar(&mut buffer, half, black_box(Fq::from(7)));
+            black_box(buffer);
+        })
+    });
+
+    c.bench_function("fold_halves batched", |b| {
+        b.iter(|| {
+            let mut buffer = table.clone();
+            fold_halves_batched(&mut buffer, half, black_box(Fq::from(7)));
+            black_box(buffer);
+        })
+    });
+
+    c.bench_function("extend_scaled scalar", |b| {
+        b.iter(|| {
+            let mut out = Vec::with_capacity(table.len());
+            extend_scaled_scalar(&mut out, black_box(Fq::from(7)), &table);
+            black_box(out);
+        })
+    });
+
+    c.bench_function("extend_scaled batched", |b| {
+        b.iter(|| {
+            let mut out = Vec::with_capacity(table.len());
+            extend_scaled_batched(&mut out, black_box(Fq::from(7)), &table);
+            black_box(out);
+        })
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -1,4 +1,5 @@
 use ark_ff::PrimeField;
+use std::ops::{Add, Neg};
 
 use crate::multilinear_polynomial_evaluation::MultilinearPoly;
 
@@ -12,6 +13,38 @@ pub struct SumPoly<F: PrimeField> {
     pub polys: Vec<ProductPoly<F>>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolyError {
+    PointLengthMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    CoefficientCountMismatch {
+        polys: usize,
+        coeffs: usize,
+    },
+    EvaluationLengthMismatch {
+        index: usize,
+        expected: usize,
+        actual: usize,
+    },
+    EmptyCombination,
+    /// Product term `index` of a sum poly is over a different number of
+    /// variables from the first one.
+    NumVarsMismatch {
+        index: usize,
+        expected: usize,
+        actual: usize,
+    },
+    /// Product term `index` of a sum poly has a different number of factors
+    /// from the first one.
+    DegreeMismatch {
+        index: usize,
+        expected: usize,
+        actual: usize,
+    },
+}
+
 impl<F: PrimeField> ProductPoly<F> {
     pub fn new(evaluations: Vec<Vec<F>>) -> Self {
         let length_1 = evaluations[0].len();
@@ -35,6 +68,23 @@ impl<F: PrimeField> ProductPoly<F> {
             .product()
     }
 
+    pub fn try_evaluate(&self, point: &[F]) -> Result<F, PolyError> {
+        let expected = self.num_of_vars();
+
+        if point.len() != expected {
+            return Err(PolyError::PointLengthMismatch {
+                expected,
+                actual: point.len(),
+            });
+        }
+
+        Ok(self.evaluate(point.to_vec()))
+    }
+
+    fn num_of_vars(&self) -> usize {
+        self.evaluation[0].num_of_vars
+    }
+
     fn partial_evaluate(&self, value: &F) -> Self {
         let partial_polys = self
             .evaluation
@@ -50,7 +100,17 @@ impl<F: PrimeField> ProductPoly<F> {
     }
 
     fn reduce(&self) -> Vec<F> {
-        (self.evaluation[0].clone() * self.evaluation[1].clone()).evaluation
+        self.evaluation[1..]
+            .iter()
+            .fold(self.evaluation[0].clone(), |acc, poly| acc * poly.clone())
+            .evaluation
+    }
+
+    fn negate(&self) -> Self {
+        let mut evaluation = self.evaluation.clone();
+        evaluation[0] = evaluation[0].scale(-F::one());
+
+        Self { evaluation }
     }
 
     fn get_degree(&self) -> usize {
@@ -60,12 +120,49 @@ impl<F: PrimeField> ProductPoly<F> {
 
 impl<F: PrimeField> SumPoly<F> {
     pub fn new(polys: Vec<ProductPoly<F>>) -> Self {
-        let degree_1 = polys[0].get_degree();
-        if polys.iter().any(|poly| poly.get_degree() != degree_1) {
-            panic!("all product polys must have same degree");
+        match Self::try_new(polys) {
+            Ok(sum_poly) => sum_poly,
+            Err(PolyError::DegreeMismatch { .. }) => {
+                panic!("all product polys must have same degree")
+            }
+            Err(PolyError::NumVarsMismatch { .. }) => {
+                panic!("all product polys must have the same number of variables")
+            }
+            Err(_) => panic!("a sum poly needs at least one product poly"),
+        }
+    }
+
+    /// Like `new`, but returns an error instead of panicking when `polys` is
+    /// empty or its terms disagree on their number of variables or factors.
+    pub fn try_new(polys: Vec<ProductPoly<F>>) -> Result<Self, PolyError> {
+        let (num_vars, degree) = match polys.first() {
+            Some(poly) => (poly.num_of_vars(), poly.get_degree()),
+            None => return Err(PolyError::EmptyCombination),
+        };
+
+        for (index, poly) in polys.iter().enumerate() {
+            if poly.num_of_vars() != num_vars {
+                return Err(PolyError::NumVarsMismatch {
+                    index,
+                    expected: num_vars,
+                    actual: poly.num_of_vars(),
+                });
+            }
+
+            if poly.get_degree() != degree {
+                return Err(PolyError::DegreeMismatch {
+                    index,
+                    expected: degree,
+                    actual: poly.get_degree(),
+                });
+            }
         }
 
-        Self { polys }
+        Ok(Self { polys })
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.polys[0].num_of_vars()
     }
 
     pub fn evaluate(&self, values: Vec<F>) -> F {
@@ -75,6 +172,19 @@ impl<F: PrimeField> SumPoly<F> {
             .sum()
     }
 
+    /// Each product term's value at `point`, in order; their sum is
+    /// `evaluate(point)`.
+    pub fn evaluate_terms(&self, point: &[F]) -> Vec<F> {
+        self.polys
+            .iter()
+            .map(|poly| poly.evaluate(point.to_vec()))
+            .collect()
+    }
+
+    pub fn try_evaluate(&self, point: &[F]) -> Result<F, PolyError> {
+        self.polys.iter().map(|poly| poly.try_evaluate(point)).sum()
+    }
+
     pub fn partial_evaluate(&self, value: &F) -> Self {
         let partial_polys = self
             .polys
@@ -86,28 +196,69 @@ impl<F: PrimeField> SumPoly<F> {
     }
 
     pub fn reduce(&self) -> Vec<F> {
-        let poly_a = &self.polys[0].reduce();
-        let poly_b = &self.polys[1].reduce();
-
-        let result = poly_a
+        self.polys[1..]
             .iter()
-            .zip(poly_b.iter())
-            .map(|(a, b)| *a + *b)
-            .collect();
+            .fold(self.polys[0].reduce(), |acc, poly| {
+                acc.iter()
+                    .zip(poly.reduce().iter())
+                    .map(|(a, b)| *a + *b)
+                    .collect()
+            })
+    }
+
+    pub fn negate(&self) -> Self {
+        Self {
+            polys: self.polys.iter().map(|poly| poly.negate()).collect(),
+        }
+    }
+
+    pub fn hypercube_sum(&self) -> F {
+        self.reduce().iter().sum()
+    }
 
-        result
+    pub fn is_zero_over_hypercube(&self) -> bool {
+        self.hypercube_sum().is_zero()
     }
 
     pub fn get_degree(&self) -> usize {
         self.polys[0].get_degree()
     }
+
+    /// Degree in each variable, and so of every sum-check round polynomial:
+    /// the largest number of multilinear factors in any product term.
+    pub fn round_degree(&self) -> usize {
+        self.polys
+            .iter()
+            .map(|poly| poly.get_degree())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<F: PrimeField> Neg for SumPoly<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.negate()
+    }
+}
+
+impl<F: PrimeField> Add for SumPoly<F> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut polys = self.polys;
+        polys.extend(other.polys);
+
+        SumPoly::new(polys)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use ark_bn254::Fq;
 
-    use super::{ProductPoly, SumPoly};
+    use super::{PolyError, ProductPoly, SumPoly};
 
     #[test]
     fn product_poly_evaluates_multiple_polys() {
@@ -206,6 +357,115 @@ mod test {
         assert_eq!(expected_result, result);
     }
 
+    #[test]
+    fn sum_poly_evaluates_each_term() {
+        let product_poly_1 = ProductPoly::new(vec![
+            vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(3)],
+            vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(2)],
+        ]);
+        let product_poly_2 = ProductPoly::new(vec![
+            vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(4)],
+            vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(5)],
+        ]);
+
+        let sum_poly = SumPoly::new(vec![product_poly_1, product_poly_2]);
+        let point = vec![Fq::from(2), Fq::from(3)];
+
+        let terms = sum_poly.evaluate_terms(&point);
+
+        assert_eq!(terms, vec![Fq::from(216), Fq::from(720)]);
+        assert_eq!(terms.iter().sum::<Fq>(), sum_poly.evaluate(point));
+    }
+
+    #[test]
+    fn it_validates_the_point_length() {
+        let product_poly = ProductPoly::new(vec![
+            vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(3)],
+            vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(2)],
+        ]);
+        let sum_poly = SumPoly::new(vec![product_poly.clone(), product_poly.clone()]);
+
+        let point = [Fq::from(2), Fq::from(3)];
+
+        assert_eq!(product_poly.try_evaluate(&point), Ok(Fq::from(216)));
+        assert_eq!(sum_poly.try_evaluate(&point), Ok(Fq::from(432)));
+
+        let mismatch = Err(PolyError::PointLengthMismatch {
+            expected: 2,
+            actual: 3,
+        });
+
+        assert_eq!(product_poly.try_evaluate(&[Fq::from(1); 3]), mismatch);
+        assert_eq!(sum_poly.try_evaluate(&[Fq::from(1); 3]), mismatch);
+        assert_eq!(
+            sum_poly.try_evaluate(&[Fq::from(1)]),
+            Err(PolyError::PointLengthMismatch {
+                expected: 2,
+                actual: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn it_rejects_product_terms_over_different_numbers_of_variables() {
+        let two_vars = ProductPoly::new(vec![
+            vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(3)],
+            vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(2)],
+        ]);
+        let one_var = ProductPoly::new(vec![
+            vec![Fq::from(1), Fq::from(2)],
+            vec![Fq::from(3), Fq::from(4)],
+        ]);
+
+        let sum_poly = SumPoly::try_new(vec![two_vars.clone(), two_vars.clone()]).unwrap();
+        assert_eq!(sum_poly.num_vars(), 2);
+
+        assert_eq!(
+            SumPoly::try_new(vec![two_vars.clone(), one_var]),
+            Err(PolyError::NumVarsMismatch {
+                index: 1,
+                expected: 2,
+                actual: 1,
+            })
+        );
+        assert_eq!(
+            SumPoly::<Fq>::try_new(Vec::new()),
+            Err(PolyError::EmptyCombination)
+        );
+    }
+
+    #[test]
+    fn sum_poly_difference_is_zero_over_hypercube() {
+        let evaluations_1 = vec![
+            vec![Fq::from(0), Fq::from(1), Fq::from(0), Fq::from(3)],
+            vec![Fq::from(0), Fq::from(7), Fq::from(0), Fq::from(2)],
+        ];
+
+        let evaluations_2 = vec![
+            vec![Fq::from(5), Fq::from(0), Fq::from(0), Fq::from(4)],
+            vec![Fq::from(1), Fq::from(0), Fq::from(0), Fq::from(5)],
+        ];
+
+        let sum_poly = SumPoly::new(vec![
+            ProductPoly::new(evaluations_1),
+            ProductPoly::new(evaluations_2),
+        ]);
+
+        assert_eq!(sum_poly.hypercube_sum(), Fq::from(38));
+        assert!(!sum_poly.is_zero_over_hypercube());
+
+        let negated = sum_poly.negate();
+        let values = vec![Fq::from(2), Fq::from(3)];
+
+        assert_eq!(negated.evaluate(values.clone()), -sum_poly.evaluate(values));
+        assert_eq!(negated.hypercube_sum(), -Fq::from(38));
+
+        let difference = sum_poly.clone() + (-sum_poly);
+
+        assert_eq!(difference.polys.len(), 4);
+        assert!(difference.is_zero_over_hypercube());
+    }
+
     #[test]
     fn sum_poly_partially_evaluates_properly() {
         let evaluations_1 = vec![
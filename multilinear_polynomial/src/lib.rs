@@ -1,2 +1,3 @@
 pub mod composed_polynomial;
 pub mod multilinear_polynomial_evaluation;
+pub mod multilinear_polynomial_serialization;
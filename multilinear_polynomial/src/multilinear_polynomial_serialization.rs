@@ -0,0 +1,172 @@
+use ark_ff::PrimeField;
+
+use crate::multilinear_polynomial_evaluation::MultilinearPoly;
+
+/// Leads every serialized poly, so files of another kind are rejected
+/// before anything is decoded.
+pub const POLY_MAGIC: [u8; 4] = *b"MLP1";
+
+const HEADER_LEN: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolySerializationError {
+    UnexpectedEnd,
+    BadMagic,
+    ChecksumMismatch,
+    InvalidFieldElement,
+    TrailingBytes,
+}
+
+impl<F: PrimeField> MultilinearPoly<F> {
+    /// The magic, `num_of_vars` as a little-endian u32, a CRC32 of
+    /// everything after the checksum, then each evaluation compressed.
+    pub fn to_bytes_with_header(&self) -> Vec<u8> {
+        let mut body = (self.num_of_vars as u32).to_le_bytes().to_vec();
+
+        for eval in &self.evaluation {
+            eval.serialize_compressed(&mut body)
+                .expect("writing to a Vec cannot fail");
+        }
+
+        let (num_vars, evaluations) = body.split_at(4);
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + evaluations.len());
+        bytes.extend_from_slice(&POLY_MAGIC);
+        bytes.extend_from_slice(num_vars);
+        bytes.extend_from_slice(&crc32(&body).to_le_bytes());
+        bytes.extend_from_slice(evaluations);
+
+        bytes
+    }
+
+    pub fn from_bytes_with_header(bytes: &[u8]) -> Result<Self, PolySerializationError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(PolySerializationError::UnexpectedEnd);
+        }
+
+        let (header, evaluations) = bytes.split_at(HEADER_LEN);
+
+        if header[..4] != POLY_MAGIC {
+            return Err(PolySerializationError::BadMagic);
+        }
+
+        let num_of_vars = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let checksum = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+
+        let mut body = header[4..8].to_vec();
+        body.extend_from_slice(evaluations);
+
+        if crc32(&body) != checksum {
+            return Err(PolySerializationError::ChecksumMismatch);
+        }
+
+        let element_size = F::zero().compressed_size();
+        let expected_len = 1usize
+            .checked_shl(num_of_vars)
+            .and_then(|len| len.checked_mul(element_size))
+            .ok_or(PolySerializationError::UnexpectedEnd)?;
+
+        if evaluations.len() < expected_len {
+            return Err(PolySerializationError::UnexpectedEnd);
+        }
+
+        if evaluations.len() > expected_len {
+            return Err(PolySerializationError::TrailingBytes);
+        }
+
+        let evaluation = evaluations
+            .chunks_exact(element_size)
+            .map(|chunk| {
+                F::deserialize_compressed(chunk)
+                    .map_err(|_| PolySerializationError::InvalidFieldElement)
+            })
+            .collect::<Result<Vec<F>, _>>()?;
+
+        Ok(Self::with_num_vars(evaluation, num_of_vars as usize))
+    }
+}
+
+/// CRC-32 (IEEE 802.3, reflected, as used by zip and PNG).
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+
+    for byte in bytes {
+        crc ^= *byte as u32;
+
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crc32, PolySerializationError, HEADER_LEN};
+    use crate::multilinear_polynomial_evaluation::MultilinearPoly;
+    use ark_bn254::Fq;
+
+    #[test]
+    fn it_computes_the_standard_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn it_round_trips_with_header() {
+        let poly = MultilinearPoly::new((1..=8).map(Fq::from).collect());
+
+        let bytes = poly.to_bytes_with_header();
+
+        assert_eq!(&bytes[..4], b"MLP1");
+        assert_eq!(bytes.len(), HEADER_LEN + 8 * 32);
+        assert_eq!(MultilinearPoly::from_bytes_with_header(&bytes), Ok(poly));
+
+        let constant = MultilinearPoly::new(vec![Fq::from(5)]);
+        assert_eq!(
+            MultilinearPoly::from_bytes_with_header(&constant.to_bytes_with_header()),
+            Ok(constant)
+        );
+    }
+
+    #[test]
+    fn it_detects_corrupted_bytes() {
+        let poly = MultilinearPoly::new((1..=4).map(Fq::from).collect());
+        let bytes = poly.to_bytes_with_header();
+
+        let mut bad_checksum = bytes.clone();
+        bad_checksum[8] ^= 1;
+        assert_eq!(
+            MultilinearPoly::<Fq>::from_bytes_with_header(&bad_checksum),
+            Err(PolySerializationError::ChecksumMismatch)
+        );
+
+        let mut bad_evaluation = bytes.clone();
+        bad_evaluation[HEADER_LEN + 40] ^= 1;
+        assert_eq!(
+            MultilinearPoly::<Fq>::from_bytes_with_header(&bad_evaluation),
+            Err(PolySerializationError::ChecksumMismatch)
+        );
+
+        let mut bad_num_vars = bytes.clone();
+        bad_num_vars[4] = 3;
+        assert_eq!(
+            MultilinearPoly::<Fq>::from_bytes_with_header(&bad_num_vars),
+            Err(PolySerializationError::ChecksumMismatch)
+        );
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(
+            MultilinearPoly::<Fq>::from_bytes_with_header(&bad_magic),
+            Err(PolySerializationError::BadMagic)
+        );
+
+        assert_eq!(
+            MultilinearPoly::<Fq>::from_bytes_with_header(&bytes[..HEADER_LEN - 1]),
+            Err(PolySerializationError::UnexpectedEnd)
+        );
+    }
+}
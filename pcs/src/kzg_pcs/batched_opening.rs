@@ -0,0 +1,179 @@
+use ark_bls12_381::{Fr, G1Projective as G1};
+use fiat_shamir::fiat_shamir_transcript::Transcript;
+use univariate_polynomial::univariate_polynomial_dense::UnivariatePoly;
+
+use crate::kzg_pcs::kzg::{Proof, VerifierKey, KZG};
+
+/// One KZG opening standing in for the two w-evaluations `o_1 = w(r_b)` and
+/// `o_2 = w(r_c)` of a GKR layer. The prover sends `q(t) = w(l(t))` on the
+/// line `l(t) = r_b + t * (r_c - r_b)`, so `q(0) = o_1` and `q(1) = o_2`, and
+/// opens `w` once at `l(tau)` for a Fiat-Shamir `tau`. `r_b` and `r_c` must
+/// be non-empty and of the same length.
+pub struct BatchedOpening {
+    pub line_evaluations: Vec<Fr>,
+    pub opened_value: Fr,
+    pub opening_proof: Proof,
+}
+
+pub fn open_batched(
+    kzg: &KZG,
+    r_b: &[Fr],
+    r_c: &[Fr],
+    transcript: &mut Transcript<Fr>,
+) -> BatchedOpening {
+    let line_evaluations: Vec<Fr> = (0..=r_b.len())
+        .map(|t| kzg.open(&line_point(r_b, r_c, Fr::from(t as u64))))
+        .collect();
+
+    transcript.append_fields(&line_evaluations);
+    let tau = transcript.get_random_challenge();
+
+    let point = line_point(r_b, r_c, tau);
+    let opened_value = kzg.open(&point);
+    let opening_proof = kzg.get_proof(opened_value, &point);
+
+    BatchedOpening {
+        line_evaluations,
+        opened_value,
+        opening_proof,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn verify_batched(
+    vk: &VerifierKey,
+    commitment: G1,
+    r_b: &[Fr],
+    r_c: &[Fr],
+    o_1: Fr,
+    o_2: Fr,
+    opening: BatchedOpening,
+    transcript: &mut Transcript<Fr>,
+) -> bool {
+    let line_evaluations = &opening.line_evaluations;
+
+    if r_b.is_empty() || r_b.len() != r_c.len() {
+        return false;
+    }
+
+    if line_evaluations.len() != r_b.len() + 1
+        || line_evaluations[0] != o_1
+        || line_evaluations[1] != o_2
+    {
+        return false;
+    }
+
+    transcript.append_fields(line_evaluations);
+    let tau = transcript.get_random_challenge();
+
+    let line_poly = UnivariatePoly::interpolate(
+        line_evaluations
+            .iter()
+            .enumerate()
+            .map(|(t, eval)| (Fr::from(t as u64), *eval))
+            .collect(),
+    );
+
+    if line_poly.evaluate(tau) != opening.opened_value {
+        return false;
+    }
+
+    vk.verify(
+        commitment,
+        opening.opened_value,
+        opening.opening_proof,
+        &line_point(r_b, r_c, tau),
+    )
+}
+
+fn line_point(r_b: &[Fr], r_c: &[Fr], t: Fr) -> Vec<Fr> {
+    r_b.iter()
+        .zip(r_c.iter())
+        .map(|(b, c)| *b + t * (*c - *b))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use multilinear_polynomial::multilinear_polynomial_evaluation::MultilinearPoly;
+
+    #[test]
+    fn test_batched_opening_of_both_w_evaluations() {
+        let w_poly =
+            MultilinearPoly::new([3, 1, 4, 1, 5, 9, 2, 6].into_iter().map(Fr::from).collect());
+
+        let kzg = KZG::new(&w_poly, vec![Fr::from(5), Fr::from(2), Fr::from(3)]);
+        let commitment = kzg.commit();
+
+        let r_b = vec![Fr::from(7), Fr::from(11), Fr::from(13)];
+        let r_c = vec![Fr::from(17), Fr::from(19), Fr::from(23)];
+
+        let o_1 = w_poly.evaluate(r_b.clone());
+        let o_2 = w_poly.evaluate(r_c.clone());
+
+        let opening = open_batched(&kzg, &r_b, &r_c, &mut Transcript::new());
+        assert!(verify_batched(
+            &kzg.verifier_key(),
+            commitment,
+            &r_b,
+            &r_c,
+            o_1,
+            o_2,
+            opening,
+            &mut Transcript::new()
+        ));
+
+        let opening = open_batched(&kzg, &r_b, &r_c, &mut Transcript::new());
+        assert!(!verify_batched(
+            &kzg.verifier_key(),
+            commitment,
+            &r_b,
+            &r_c,
+            o_1,
+            o_2 + Fr::from(1),
+            opening,
+            &mut Transcript::new()
+        ));
+    }
+
+    #[test]
+    fn test_verify_batched_rejects_mismatched_points() {
+        let w_poly = MultilinearPoly::new([3, 1, 4, 1].into_iter().map(Fr::from).collect());
+
+        let kzg = KZG::new(&w_poly, vec![Fr::from(5), Fr::from(2)]);
+        let commitment = kzg.commit();
+
+        let r_b = vec![Fr::from(7), Fr::from(11)];
+        let r_c = vec![Fr::from(17), Fr::from(19)];
+        let (o_1, o_2) = (w_poly.evaluate(r_b.clone()), w_poly.evaluate(r_c.clone()));
+
+        let opening = open_batched(&kzg, &r_b, &r_c, &mut Transcript::new());
+        assert!(!verify_batched(
+            &kzg.verifier_key(),
+            commitment,
+            &r_b,
+            &r_c[..1],
+            o_1,
+            o_2,
+            opening,
+            &mut Transcript::new()
+        ));
+
+        let opening = BatchedOpening {
+            line_evaluations: vec![o_1],
+            opened_value: o_1,
+            opening_proof: Proof { quotients: vec![] },
+        };
+        assert!(!verify_batched(
+            &kzg.verifier_key(),
+            commitment,
+            &[],
+            &[],
+            o_1,
+            o_2,
+            opening,
+            &mut Transcript::new()
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+use ark_bls12_381::{Fr, G1Projective as G1};
+use gkr::{
+    gkr_circuit::Circuit,
+    gkr_protocol::{self, prove_with_transcript, verify_with_input_claims},
+};
+
+use crate::kzg_pcs::{
+    batched_opening::{open_batched, verify_batched, BatchedOpening},
+    kzg::{commitment_transcript, VerifierKey, KZG},
+};
+
+/// A GKR proof for committed inputs: the verifier never sees them, and the
+/// two claims about the input poly the bottom layer ends on are opened
+/// together against the commitment.
+pub struct CommittedInputProof {
+    pub gkr_proof: gkr_protocol::Proof<Fr>,
+    pub input_opening: BatchedOpening,
+}
+
+/// Proves `circuit` on `inputs`, where `kzg` holds `inputs` as a multilinear
+/// poly.
+pub fn prove_committed_input(
+    circuit: &mut Circuit<Fr>,
+    inputs: &[Fr],
+    kzg: &KZG,
+) -> CommittedInputProof {
+    let mut transcript = commitment_transcript(kzg.commit());
+    let start = transcript.clone();
+
+    let gkr_proof = prove_with_transcript(circuit, inputs, &mut transcript);
+
+    let challenges = gkr_proof
+        .derive_challenges_in(circuit, &mut start.clone())
+        .expect("a proof fresh from `prove_with_transcript` has the circuit's shape");
+    let bottom = &challenges[challenges.len() - 1].sum_check;
+    let (r_b, r_c) = bottom.split_at(bottom.len() / 2);
+
+    let input_opening = open_batched(kzg, r_b, r_c, &mut transcript);
+
+    CommittedInputProof {
+        gkr_proof,
+        input_opening,
+    }
+}
+
+pub fn verify_committed_input(
+    proof: CommittedInputProof,
+    circuit: &Circuit<Fr>,
+    vk: &VerifierKey,
+    commitment: G1,
+) -> bool {
+    let line_evaluations = &proof.input_opening.line_evaluations;
+
+    if line_evaluations.len() < 2 {
+        return false;
+    }
+
+    let (o_1, o_2) = (line_evaluations[0], line_evaluations[1]);
+    let mut transcript = commitment_transcript(commitment);
+
+    let challenges =
+        match verify_with_input_claims(&proof.gkr_proof, circuit, (o_1, o_2), &mut transcript) {
+            Ok(challenges) => challenges,
+            Err(_) => return false,
+        };
+    let (r_b, r_c) = challenges.input_points();
+
+    verify_batched(
+        vk,
+        commitment,
+        r_b,
+        r_c,
+        o_1,
+        o_2,
+        proof.input_opening,
+        &mut transcript,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gkr::gkr_circuit::Operation;
+    use multilinear_polynomial::multilinear_polynomial_evaluation::MultilinearPoly;
+
+    fn sample_circuit() -> Circuit<Fr> {
+        Circuit::new(vec![
+            vec![Operation::Mul, Operation::Mul],
+            vec![Operation::Add],
+        ])
+    }
+
+    fn input_commitment(inputs: &[Fr]) -> (KZG, G1) {
+        let kzg = KZG::new(
+            &MultilinearPoly::new(inputs.to_vec()),
+            vec![Fr::from(5), Fr::from(2)],
+        );
+        let commitment = kzg.commit();
+
+        (kzg, commitment)
+    }
+
+    #[test]
+    fn test_prove_and_verify_committed_input() {
+        let inputs: Vec<Fr> = vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
+        let (kzg, commitment) = input_commitment(&inputs);
+
+        let mut circuit = sample_circuit();
+        let proof = prove_committed_input(&mut circuit, &inputs, &kzg);
+        assert_eq!(proof.input_opening.line_evaluations.len(), 3);
+
+        assert!(verify_committed_input(
+            proof,
+            &circuit,
+            &kzg.verifier_key(),
+            commitment
+        ));
+    }
+
+    #[test]
+    fn test_verify_committed_input_rejects_other_inputs() {
+        let inputs: Vec<Fr> = vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
+        let (kzg, _) = input_commitment(&inputs);
+        let (_, other_commitment) =
+            input_commitment(&[Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(5)]);
+
+        let mut circuit = sample_circuit();
+        let proof = prove_committed_input(&mut circuit, &inputs, &kzg);
+        assert!(!verify_committed_input(
+            proof,
+            &circuit,
+            &kzg.verifier_key(),
+            other_commitment
+        ));
+
+        let mut proof = prove_committed_input(&mut circuit, &inputs, &kzg);
+        proof.input_opening.line_evaluations[0] += Fr::from(1);
+        assert!(!verify_committed_input(
+            proof,
+            &circuit,
+            &kzg.verifier_key(),
+            kzg.commit()
+        ));
+    }
+}
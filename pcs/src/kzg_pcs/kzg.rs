@@ -1,16 +1,19 @@
 use ark_bls12_381::{Bls12_381, Fr, G1Projective as G1, G2Projective as G2};
 use ark_ec::{pairing::Pairing, AffineRepr, PrimeGroup, ScalarMul};
 use ark_ff::PrimeField;
-use gkr::{gkr_circuit::Operation, gkr_protocol::tensor_add_mul_polynomials};
+use ark_serialize::CanonicalSerialize;
+use fiat_shamir::fiat_shamir_transcript::Transcript;
+use gkr::gkr_circuit::{tensor_add_mul_polynomials, Operation};
 use multilinear_polynomial::multilinear_polynomial_evaluation::MultilinearPoly;
 
 //todo change G1, G2 to Pairing
 
-struct Proof {
-    quotients: Vec<G1>,
+pub struct Proof {
+    pub quotients: Vec<G1>,
 }
 
-struct KZG {
+#[allow(clippy::upper_case_acronyms)]
+pub struct KZG {
     polynomial: MultilinearPoly<Fr>,
     g_1: G1,
     g_2: G2,
@@ -18,8 +21,17 @@ struct KZG {
     g1_lagrange_basis: Vec<G1>,
 }
 
+/// The part of the setup a verifier needs: it can check openings against a
+/// commitment without holding the committed polynomial.
+#[derive(Clone)]
+pub struct VerifierKey {
+    g_1: G1,
+    g_2: G2,
+    g2_taus: Vec<G2>,
+}
+
 impl KZG {
-    fn new(polynomial: &MultilinearPoly<Fr>, taus: Vec<Fr>) -> Self {
+    pub fn new(polynomial: &MultilinearPoly<Fr>, taus: Vec<Fr>) -> Self {
         if taus.len() != polynomial.num_of_vars {
             panic!("invalid taus or polynomials");
         }
@@ -56,15 +68,15 @@ impl KZG {
         (g2_taus, lagrange_basis)
     }
 
-    fn commit(&self) -> G1 {
+    pub fn commit(&self) -> G1 {
         evaluate_poly_with_l_basis_in_g1(&self.polynomial.evaluation, &self.g1_lagrange_basis)
     }
 
-    fn open(&self, opening_values: &[Fr]) -> Fr {
+    pub fn open(&self, opening_values: &[Fr]) -> Fr {
         self.polynomial.evaluate(opening_values.to_vec())
     }
 
-    fn get_proof(&self, opened_value: Fr, opening_values: &[Fr]) -> Proof {
+    pub fn get_proof(&self, opened_value: Fr, opening_values: &[Fr]) -> Proof {
         let mut poly_minus_v = MultilinearPoly::new(
             self.polynomial
                 .evaluation
@@ -98,15 +110,38 @@ impl KZG {
         Proof { quotients: q_i }
     }
 
-    fn verify(
+    pub fn verifier_key(&self) -> VerifierKey {
+        VerifierKey {
+            g_1: self.g_1,
+            g_2: self.g_2,
+            g2_taus: self.g2_taus.clone(),
+        }
+    }
+
+    pub fn verify(
+        &self,
+        commitment: G1,
+        opened_value: Fr,
+        proof: Proof,
+        opening_values: &[Fr],
+    ) -> bool {
+        self.verifier_key()
+            .verify(commitment, opened_value, proof, opening_values)
+    }
+}
+
+impl VerifierKey {
+    pub fn verify(
         &self,
         commitment: G1,
         opened_value: Fr,
         proof: Proof,
         opening_values: &[Fr],
     ) -> bool {
-        if proof.quotients.len() != opening_values.len() {
-            panic!("num of quotients in proof not equal to num of opening values");
+        if proof.quotients.len() != opening_values.len()
+            || opening_values.len() != self.g2_taus.len()
+        {
+            return false;
         }
 
         let lhs = commitment - self.g_1.mul_bigint(opened_value.into_bigint());
@@ -114,9 +149,9 @@ impl KZG {
 
         let mut rhs = Vec::with_capacity(opening_values.len());
 
-        for i in 0..opening_values.len() {
+        for (i, opening_value) in opening_values.iter().enumerate() {
             let quotient = proof.quotients[i];
-            let opening_value_g2 = self.g_2.mul_bigint(opening_values[i].into_bigint());
+            let opening_value_g2 = self.g_2.mul_bigint(opening_value.into_bigint());
             let factor = self.g2_taus[i] - opening_value_g2;
 
             let rhs_i = Bls12_381::pairing(quotient, factor);
@@ -129,6 +164,20 @@ impl KZG {
     }
 }
 
+/// Starts a transcript with `commitment`, so every challenge drawn from it
+/// comes after the committed polynomial is fixed.
+pub(crate) fn commitment_transcript(commitment: G1) -> Transcript<Fr> {
+    let mut bytes = Vec::new();
+    commitment
+        .serialize_compressed(&mut bytes)
+        .expect("writing to a Vec cannot fail");
+
+    let mut transcript = Transcript::new();
+    transcript.append(&bytes);
+
+    transcript
+}
+
 fn evaluate_poly_with_l_basis_in_g1(poly_evaluations: &[Fr], lagrange_basis: &[G1]) -> G1 {
     if poly_evaluations.len() != lagrange_basis.len() {
         panic!("invalid polynomial or lagrange basis");
@@ -190,12 +239,11 @@ fn get_lagrange_basis(num_of_vars: usize, unenc_taus: &[Fr], g_1: G1) -> Vec<G1>
         let mut layer_eval = Fr::from(1);
 
         for (i, bit) in layer.iter().enumerate() {
-            let bit_scalar;
-            if *bit == 0 {
-                bit_scalar = Fr::from(1) - unenc_taus[i];
+            let bit_scalar = if *bit == 0 {
+                Fr::from(1) - unenc_taus[i]
             } else {
-                bit_scalar = unenc_taus[i];
-            }
+                unenc_taus[i]
+            };
 
             layer_eval *= bit_scalar;
         }
@@ -416,7 +464,7 @@ mod test {
 
         let is_verified = kzg_instance.verify(commitment, opened_value, proof, opening_values);
 
-        assert_eq!(is_verified, true);
+        assert!(is_verified);
     }
 
     #[test]
@@ -444,6 +492,6 @@ mod test {
         let is_verified =
             kzg_instance.verify(commitment, opened_value, invalid_proof, opening_values);
 
-        assert_eq!(is_verified, false);
+        assert!(!is_verified);
     }
 }
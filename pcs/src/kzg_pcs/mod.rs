@@ -1 +1,4 @@
+pub mod batched_opening;
+pub mod committed_input;
 pub mod kzg;
+pub mod output_equality;
@@ -0,0 +1,152 @@
+use ark_bls12_381::{Fr, G1Projective as G1};
+use fiat_shamir::fiat_shamir_transcript::Transcript;
+use gkr::{
+    gkr_challenges::ChallengeStream,
+    gkr_circuit::Circuit,
+    gkr_protocol::{self, prove_with_transcript, verify_in_transcript},
+};
+
+use crate::kzg_pcs::kzg::{commitment_transcript, Proof, VerifierKey, KZG};
+
+/// A GKR proof together with a KZG opening of the committed vector at the
+/// output challenge.
+pub struct OutputEqualityProof {
+    pub gkr_proof: gkr_protocol::Proof<Fr>,
+    pub opened_value: Fr,
+    pub opening_proof: Proof,
+}
+
+/// The output challenge the GKR run on `transcript` draws first.
+fn output_claim(transcript: &Transcript<Fr>, proof: &gkr_protocol::Proof<Fr>) -> (Fr, Fr) {
+    ChallengeStream::new(&mut transcript.clone()).output_claim(proof.output_poly())
+}
+
+/// Proves `circuit(inputs) == v`, where `kzg` holds the committed vector `v`
+/// laid out like the GKR output poly.
+pub fn prove_output_equals(
+    circuit: &mut Circuit<Fr>,
+    inputs: &[Fr],
+    kzg: &KZG,
+) -> OutputEqualityProof {
+    let mut transcript = commitment_transcript(kzg.commit());
+    let start = transcript.clone();
+
+    let gkr_proof = prove_with_transcript(circuit, inputs, &mut transcript);
+    let (_, output_challenge) = output_claim(&start, &gkr_proof);
+
+    let opened_value = kzg.open(&[output_challenge]);
+    let opening_proof = kzg.get_proof(opened_value, &[output_challenge]);
+
+    OutputEqualityProof {
+        gkr_proof,
+        opened_value,
+        opening_proof,
+    }
+}
+
+pub fn verify_output_equals(
+    proof: OutputEqualityProof,
+    circuit: Circuit<Fr>,
+    inputs: &[Fr],
+    vk: &VerifierKey,
+    commitment: G1,
+) -> bool {
+    let mut transcript = commitment_transcript(commitment);
+
+    if proof.gkr_proof.output_poly().num_of_vars != 1 || proof.opening_proof.quotients.len() != 1 {
+        return false;
+    }
+
+    let (output_claim, output_challenge) = output_claim(&transcript, &proof.gkr_proof);
+
+    if proof.opened_value != output_claim {
+        return false;
+    }
+
+    if !vk.verify(
+        commitment,
+        proof.opened_value,
+        proof.opening_proof,
+        &[output_challenge],
+    ) {
+        return false;
+    }
+
+    verify_in_transcript(proof.gkr_proof, circuit, inputs, &mut transcript).is_ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gkr::gkr_circuit::Operation;
+    use multilinear_polynomial::multilinear_polynomial_evaluation::MultilinearPoly;
+
+    fn output_commitment(vector: Vec<Fr>) -> (KZG, G1) {
+        let kzg = KZG::new(&MultilinearPoly::new(vector), vec![Fr::from(5)]);
+        let commitment = kzg.commit();
+
+        (kzg, commitment)
+    }
+
+    fn sample_circuit() -> Circuit<Fr> {
+        Circuit::new(vec![
+            vec![Operation::Mul, Operation::Mul],
+            vec![Operation::Add],
+        ])
+    }
+
+    #[test]
+    fn test_prove_output_equals_committed_vector() {
+        let inputs: Vec<Fr> = vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
+
+        let (kzg, commitment) = output_commitment(vec![Fr::from(14), Fr::from(0)]);
+
+        let mut circuit = sample_circuit();
+        let proof = prove_output_equals(&mut circuit, &inputs, &kzg);
+
+        assert!(verify_output_equals(
+            proof,
+            circuit,
+            &inputs,
+            &kzg.verifier_key(),
+            commitment
+        ));
+    }
+
+    #[test]
+    fn test_prove_output_equals_rejects_other_vector() {
+        let inputs: Vec<Fr> = vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
+
+        let (kzg, commitment) = output_commitment(vec![Fr::from(15), Fr::from(0)]);
+
+        let mut circuit = sample_circuit();
+        let proof = prove_output_equals(&mut circuit, &inputs, &kzg);
+
+        assert!(!verify_output_equals(
+            proof,
+            circuit,
+            &inputs,
+            &kzg.verifier_key(),
+            commitment
+        ));
+    }
+
+    #[test]
+    fn test_verify_output_equals_rejects_a_swapped_commitment() {
+        let inputs: Vec<Fr> = vec![Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
+
+        let (kzg, _) = output_commitment(vec![Fr::from(14), Fr::from(0)]);
+        let (_, other_commitment) = output_commitment(vec![Fr::from(14), Fr::from(1)]);
+
+        let mut circuit = sample_circuit();
+        let proof = prove_output_equals(&mut circuit, &inputs, &kzg);
+
+        assert!(!verify_output_equals(
+            proof,
+            circuit,
+            &inputs,
+            &kzg.verifier_key(),
+            other_commitment
+        ));
+    }
+}
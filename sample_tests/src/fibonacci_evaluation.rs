@@ -9,7 +9,7 @@ fn check_test(x: Fq, poly: &UnivariatePoly<Fq>) {
 }
 
 pub fn fibonacci_check() {
-    let fib_points = vec![
+    let fib_points = [
         Fq::from(1),
         Fq::from(1),
         Fq::from(2),
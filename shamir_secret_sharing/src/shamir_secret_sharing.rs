@@ -3,6 +3,7 @@ use ark_std::rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use univariate_polynomial::univariate_polynomial_dense::UnivariatePoly;
 
+#[allow(dead_code)]
 fn create_polynomia(threshold: usize, secret_value: Fq, secret_point: Fq) -> UnivariatePoly<Fq> {
     let mut points = vec![];
     let base_point = (secret_point, secret_value);
@@ -18,11 +19,10 @@ fn create_polynomia(threshold: usize, secret_value: Fq, secret_point: Fq) -> Uni
         points.push((random_x_point, random_y_point));
     }
 
-    let poly = UnivariatePoly::interpolate(points);
-
-    poly
+    UnivariatePoly::interpolate(points)
 }
 
+#[allow(dead_code)]
 fn recover_polynomial(points: Vec<(Fq, Fq)>, threshold: usize) -> UnivariatePoly<Fq> {
     if points.len() < threshold {
         panic!("Not enough points to recreate polynomial");
@@ -37,12 +37,12 @@ fn recover_polynomial(points: Vec<(Fq, Fq)>, threshold: usize) -> UnivariatePoly
     UnivariatePoly::interpolate(selected_points)
 }
 
+#[allow(dead_code)]
 fn get_secret(poly: &UnivariatePoly<Fq>, x_point: Fq) -> Fq {
-    let secret = poly.evaluate(Fq::from(x_point));
-
-    secret
+    poly.evaluate(Fq::from(x_point))
 }
 
+#[allow(dead_code)]
 fn share_points(
     num_of_shares: usize,
     threshold: usize,
@@ -1,5 +1,8 @@
 use ark_ff::PrimeField;
-use fiat_shamir::fiat_shamir_transcript::{fq_vec_to_bytes, Transcript};
+use fiat_shamir::{
+    challenge_source::ChallengeSource,
+    fiat_shamir_transcript::{field_vec_to_bytes, Transcript},
+};
 use multilinear_polynomial::{
     composed_polynomial::SumPoly, multilinear_polynomial_evaluation::MultilinearPoly,
 };
@@ -16,27 +19,41 @@ pub struct GkrProof<F: PrimeField> {
     pub random_challenges: Vec<F>,
 }
 
+/// `oracle_evaluation` is the oracle's value at the final point, or `None`
+/// if a round failed before the oracle was queried.
 pub struct GkrVerify<F: PrimeField> {
     pub verified: bool,
     pub final_claimed_sum: F,
     pub random_challenges: Vec<F>,
+    pub oracle_evaluation: Option<F>,
+}
+
+impl<F: PrimeField> GkrVerify<F> {
+    fn rejected() -> Self {
+        Self {
+            verified: false,
+            final_claimed_sum: F::zero(),
+            random_challenges: vec![F::zero()],
+            oracle_evaluation: None,
+        }
+    }
 }
 
 pub fn prove<F: PrimeField>(polynomial: &MultilinearPoly<F>) -> Proof<F> {
     let mut transcript = Transcript::<F>::new();
-    transcript.append(&fq_vec_to_bytes(&polynomial.evaluation));
+    transcript.append(&field_vec_to_bytes(&polynomial.evaluation));
 
     let claimed_sum: F = polynomial.evaluation.iter().sum();
-    transcript.append(&fq_vec_to_bytes(&[claimed_sum]));
+    transcript.append(&field_vec_to_bytes(&[claimed_sum]));
 
     let num_rounds = polynomial.num_of_vars;
-    let mut proof_polynomials = Vec::with_capacity(num_rounds as usize);
+    let mut proof_polynomials = Vec::with_capacity(num_rounds);
     let mut current_poly = polynomial.clone();
 
     for _ in 0..num_rounds {
         let proof_poly = get_round_partial_polynomial_proof(&current_poly.evaluation);
 
-        transcript.append(&fq_vec_to_bytes(&proof_poly));
+        transcript.append(&field_vec_to_bytes(&proof_poly));
 
         proof_polynomials.push(proof_poly);
 
@@ -53,8 +70,8 @@ pub fn prove<F: PrimeField>(polynomial: &MultilinearPoly<F>) -> Proof<F> {
 
 pub fn verify<F: PrimeField>(polynomial: &MultilinearPoly<F>, proof: Proof<F>) -> bool {
     let mut transcript = Transcript::<F>::new();
-    transcript.append(&fq_vec_to_bytes(&polynomial.evaluation));
-    transcript.append(&fq_vec_to_bytes(&[proof.claimed_sum]));
+    transcript.append(&field_vec_to_bytes(&polynomial.evaluation));
+    transcript.append(&field_vec_to_bytes(&[proof.claimed_sum]));
 
     let mut current_poly = polynomial.clone();
     let mut random_challenges = Vec::with_capacity(proof.proof_polynomials.len());
@@ -67,7 +84,7 @@ pub fn verify<F: PrimeField>(polynomial: &MultilinearPoly<F>, proof: Proof<F>) -
             return false;
         }
 
-        transcript.append(&fq_vec_to_bytes(&poly.evaluation));
+        transcript.append(&field_vec_to_bytes(&poly.evaluation));
         let random_challenge = transcript.get_random_challenge();
 
         expected_sum =
@@ -86,7 +103,7 @@ pub fn verify<F: PrimeField>(polynomial: &MultilinearPoly<F>, proof: Proof<F>) -
 pub fn gkr_prove<F: PrimeField>(
     claimed_sum: F,
     composed_polynomial: &SumPoly<F>,
-    transcript: &mut Transcript<F>,
+    transcript: &mut impl ChallengeSource<F>,
 ) -> GkrProof<F> {
     let num_rounds = composed_polynomial.polys[0].evaluation[0].num_of_vars;
     let mut proof_polynomials = Vec::with_capacity(num_rounds);
@@ -96,7 +113,7 @@ pub fn gkr_prove<F: PrimeField>(
     for _ in 0..num_rounds {
         let proof_poly = get_round_partial_polynomial_proof_gkr(&current_poly); //this is f(b) then f(c)
 
-        transcript.append(&fq_vec_to_bytes(&proof_poly));
+        transcript.append_fields(&proof_poly);
 
         proof_polynomials.push(proof_poly);
 
@@ -114,55 +131,62 @@ pub fn gkr_prove<F: PrimeField>(
     }
 }
 
+/// The check every round starts with, `g(0) + g(1) == claimed_sum`, where
+/// `round_poly` holds `g` evaluated at 0, 1, 2, ... `gkr_verify` runs the same
+/// check through `UnivariatePoly::from_sumcheck_round`.
+pub fn check_first_round<F: PrimeField>(round_poly: &[F], claimed_sum: F) -> bool {
+    UnivariatePoly::from_sumcheck_round(round_poly, claimed_sum).is_ok()
+}
+
+/// Checks every round, then queries `oracle` for the composed polynomial at
+/// the point made of all round challenges and accepts only if it equals the
+/// final round's claim.
 pub fn gkr_verify<F: PrimeField>(
     round_polys: Vec<Vec<F>>,
     mut claimed_sum: F,
-    transcript: &mut Transcript<F>,
+    round_degree: usize,
+    transcript: &mut impl ChallengeSource<F>,
+    oracle: impl Fn(&[F]) -> F,
 ) -> GkrVerify<F> {
+    // Without a single round nothing ties `claimed_sum` to the polynomial,
+    // so it would be handed back unchecked as the final claim.
+    if round_polys.is_empty() {
+        return GkrVerify::rejected();
+    }
+
     let mut random_challenges = Vec::new();
 
     for round_poly in round_polys {
-        let f_b_0 = round_poly[0];
-        let f_b_1 = round_poly[1];
-
-        if f_b_0 + f_b_1 != claimed_sum {
-            return GkrVerify {
-                verified: false,
-                final_claimed_sum: F::zero(),
-                random_challenges: vec![F::zero()],
-            };
+        if round_poly.len() != round_degree + 1 {
+            return GkrVerify::rejected();
         }
 
-        transcript.append(&fq_vec_to_bytes(&round_poly));
+        let round_uni_poly = match UnivariatePoly::from_sumcheck_round(&round_poly, claimed_sum) {
+            Ok(round_uni_poly) => round_uni_poly,
+            Err(_) => return GkrVerify::rejected(),
+        };
+
+        transcript.append_fields(&round_poly);
 
         let r_c = transcript.get_random_challenge();
 
         random_challenges.push(r_c);
 
-        let round_uni_points = round_poly
-            .iter()
-            .enumerate()
-            .map(|(i, y)| {
-                let x = F::from(i as u64);
-
-                (x, *y)
-            })
-            .collect();
-
-        let round_uni_poly = UnivariatePoly::interpolate(round_uni_points);
-
         claimed_sum = round_uni_poly.evaluate(r_c); //next expected sum
     }
 
+    let oracle_evaluation = oracle(&random_challenges);
+
     GkrVerify {
-        verified: true,
+        verified: oracle_evaluation == claimed_sum,
         final_claimed_sum: claimed_sum,
         random_challenges,
+        oracle_evaluation: Some(oracle_evaluation),
     }
 }
 
 fn get_round_partial_polynomial_proof_gkr<F: PrimeField>(composed_poly: &SumPoly<F>) -> Vec<F> {
-    let degree = composed_poly.get_degree();
+    let degree = composed_poly.round_degree();
 
     (0..=degree)
         .map(|i| {
@@ -193,7 +217,7 @@ mod test {
 
     use crate::sum_check_protocol::{prove, verify, Proof};
 
-    use super::{get_round_partial_polynomial_proof_gkr, gkr_prove, gkr_verify};
+    use super::{check_first_round, get_round_partial_polynomial_proof_gkr, gkr_prove, gkr_verify};
 
     #[test]
     fn test_valid_proving_and_verification() {
@@ -212,7 +236,7 @@ mod test {
 
         let is_verified = verify(&initial_polynomial, proof);
 
-        assert_eq!(is_verified, true);
+        assert!(is_verified);
     }
 
     #[test]
@@ -230,7 +254,7 @@ mod test {
 
         let is_verified = verify(&initial_polynomial, false_proof);
 
-        assert_eq!(is_verified, false);
+        assert!(!is_verified);
     }
 
     #[test]
@@ -251,6 +275,15 @@ mod test {
 
         assert_eq!(round_poly, expected_round_poly);
     }
+    #[test]
+    fn test_check_first_round() {
+        let round_poly = vec![Fq::from(5), Fq::from(7), Fq::from(13)];
+
+        assert!(check_first_round(&round_poly, Fq::from(12)));
+        assert!(!check_first_round(&round_poly, Fq::from(13)));
+        assert!(!check_first_round(&[Fq::from(12)], Fq::from(12)));
+    }
+
     #[test]
     fn test_gkr_prover_and_verifier() {
         let poly1a = MultilinearPoly::new(vec![Fq::from(0), Fq::from(0), Fq::from(0), Fq::from(2)]);
@@ -270,9 +303,83 @@ mod test {
         let verified = gkr_verify(
             result.proof_polynomials,
             result.claimed_sum,
+            fbc_poly.round_degree(),
             &mut transcript2,
+            |point| fbc_poly.evaluate(point.to_vec()),
         );
 
-        assert_eq!(verified.verified, true);
+        assert!(verified.verified);
+    }
+
+    #[test]
+    fn test_gkr_verify_queries_the_oracle_at_the_final_point() {
+        let fbc_poly = SumPoly::new(vec![ProductPoly::new(vec![
+            vec![Fq::from(0), Fq::from(4), Fq::from(1), Fq::from(2)],
+            vec![Fq::from(3), Fq::from(0), Fq::from(8), Fq::from(3)],
+        ])]);
+        let claimed_sum = fbc_poly.hypercube_sum();
+
+        let verify_with = |oracle: &dyn Fn(&[Fq]) -> Fq| {
+            let proof = gkr_prove(claimed_sum, &fbc_poly, &mut Transcript::new());
+
+            gkr_verify(
+                proof.proof_polynomials,
+                claimed_sum,
+                fbc_poly.round_degree(),
+                &mut Transcript::new(),
+                oracle,
+            )
+        };
+
+        let verified = verify_with(&|point| fbc_poly.evaluate(point.to_vec()));
+        assert!(verified.verified);
+        assert_eq!(verified.oracle_evaluation, Some(verified.final_claimed_sum));
+        assert_eq!(verified.random_challenges.len(), 2);
+
+        let verified = verify_with(&|point| fbc_poly.evaluate(point.to_vec()) + Fq::from(1));
+        assert!(!verified.verified);
+        assert_eq!(
+            verified.oracle_evaluation,
+            Some(verified.final_claimed_sum + Fq::from(1))
+        );
+    }
+
+    #[test]
+    fn test_gkr_verify_rejects_empty_round_polys() {
+        let verified = gkr_verify(Vec::new(), Fq::from(12), 2, &mut Transcript::new(), |_| {
+            Fq::from(12)
+        });
+
+        assert!(!verified.verified);
+        assert_eq!(verified.oracle_evaluation, None);
+    }
+
+    #[test]
+    fn test_gkr_sum_check_on_difference_poly() {
+        let product_poly = ProductPoly::new(vec![
+            vec![Fq::from(0), Fq::from(4), Fq::from(1), Fq::from(2)],
+            vec![Fq::from(3), Fq::from(0), Fq::from(8), Fq::from(3)],
+        ]);
+        let sum_poly = SumPoly::new(vec![product_poly.clone(), product_poly]);
+
+        let difference = sum_poly.clone() + (-sum_poly);
+
+        let mut transcript1 = Transcript::new();
+        let mut transcript2 = Transcript::new();
+
+        let result = gkr_prove(Fq::from(0), &difference, &mut transcript1);
+        let verified = gkr_verify(
+            result.proof_polynomials,
+            Fq::from(0),
+            difference.round_degree(),
+            &mut transcript2,
+            |point| difference.evaluate(point.to_vec()),
+        );
+
+        assert!(verified.verified);
+        assert_eq!(
+            verified.final_claimed_sum,
+            difference.evaluate(verified.random_challenges)
+        );
     }
 }
@@ -6,11 +6,29 @@ pub struct UnivariatePoly<F: PrimeField> {
     pub coefficient: Vec<F>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SumCheckError {
+    TooFewEvaluations { len: usize },
+    RoundSumMismatch,
+}
+
 impl<F: PrimeField> UnivariatePoly<F> {
     pub fn new(coeff: Vec<F>) -> Self {
         UnivariatePoly { coefficient: coeff }
     }
 
+    pub fn zero() -> Self {
+        UnivariatePoly::new(vec![])
+    }
+
+    pub fn one() -> Self {
+        UnivariatePoly::new(vec![F::one()])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.iter().all(|coeff| coeff.is_zero())
+    }
+
     fn trim(&mut self) {
         while self.coefficient.last() == Some(&F::zero()) {
             self.coefficient.pop();
@@ -21,14 +39,14 @@ impl<F: PrimeField> UnivariatePoly<F> {
         self.coefficient
             .iter()
             .enumerate()
-            .map(|(index, coeff)| *coeff * x.pow(&[index as u64]))
+            .map(|(index, coeff)| *coeff * x.pow([index as u64]))
             .sum()
     }
 
     pub fn degree(&mut self) -> usize {
         self.trim();
 
-        self.coefficient.len() - 1
+        self.coefficient.len().saturating_sub(1)
     }
 
     fn scalar_mul(&self, scalar: F) -> Self {
@@ -46,17 +64,13 @@ impl<F: PrimeField> UnivariatePoly<F> {
     }
 
     pub fn interpolate(points: Vec<(F, F)>) -> UnivariatePoly<F> {
-        let n = points.len();
         let mut result = UnivariatePoly::new(vec![F::zero()]);
 
-        for i in 0..n {
-            let (x_i, y_i) = points[i];
+        for (i, &(x_i, y_i)) in points.iter().enumerate() {
             let mut l_i = UnivariatePoly::new(vec![F::one()]);
 
-            for j in 0..n {
+            for (j, &(x_j, _)) in points.iter().enumerate() {
                 if i != j {
-                    let (x_j, _) = points[j];
-
                     let numerator = UnivariatePoly::new(vec![-x_j, F::one()]);
 
                     let denominator = x_i - x_j;
@@ -72,6 +86,26 @@ impl<F: PrimeField> UnivariatePoly<F> {
 
         result
     }
+
+    /// Interpolates a sum-check round polynomial from `[g(0), g(1), g(2), ...]`
+    /// after checking `g(0) + g(1) == expected_sum`.
+    pub fn from_sumcheck_round(evals: &[F], expected_sum: F) -> Result<Self, SumCheckError> {
+        if evals.len() < 2 {
+            return Err(SumCheckError::TooFewEvaluations { len: evals.len() });
+        }
+
+        if evals[0] + evals[1] != expected_sum {
+            return Err(SumCheckError::RoundSumMismatch);
+        }
+
+        let points = evals
+            .iter()
+            .enumerate()
+            .map(|(i, y)| (F::from(i as u64), *y))
+            .collect();
+
+        Ok(Self::interpolate(points))
+    }
 }
 
 impl<F: PrimeField> Add for UnivariatePoly<F> {
@@ -113,6 +147,26 @@ mod test {
     use super::*;
     use ark_bn254::Fq;
 
+    #[test]
+    fn it_builds_sumcheck_round_polys() {
+        // g(x) = 2x^2 + 3x + 1
+        let evals = [Fq::from(1), Fq::from(6), Fq::from(15)];
+
+        let mut round_poly = UnivariatePoly::from_sumcheck_round(&evals, Fq::from(7)).unwrap();
+
+        assert_eq!(round_poly.degree(), 2);
+        assert_eq!(round_poly.evaluate(Fq::from(4)), Fq::from(45));
+
+        assert_eq!(
+            UnivariatePoly::from_sumcheck_round(&evals, Fq::from(8)).map(|_| ()),
+            Err(SumCheckError::RoundSumMismatch)
+        );
+        assert_eq!(
+            UnivariatePoly::from_sumcheck_round(&evals[..1], Fq::from(1)).map(|_| ()),
+            Err(SumCheckError::TooFewEvaluations { len: 1 })
+        );
+    }
+
     #[test]
     fn it_returns_degree() {
         let mut poly_1: UnivariatePoly<Fq> = UnivariatePoly {
@@ -122,6 +176,43 @@ mod test {
         assert!(poly_1.degree() == 2);
     }
 
+    #[test]
+    fn it_creates_zero_and_one_polys() {
+        let mut zero: UnivariatePoly<Fq> = UnivariatePoly::zero();
+        let mut one: UnivariatePoly<Fq> = UnivariatePoly::one();
+
+        assert!(zero.is_zero());
+        assert!(!one.is_zero());
+        assert_eq!(zero.evaluate(Fq::from(7)), Fq::from(0));
+        assert_eq!(one.evaluate(Fq::from(7)), Fq::from(1));
+        assert_eq!(zero.degree(), 0);
+        assert_eq!(one.degree(), 0);
+    }
+
+    #[test]
+    fn it_checks_zero_after_normalization() {
+        let mut poly: UnivariatePoly<Fq> = UnivariatePoly::new(vec![Fq::from(0), Fq::from(0)]);
+
+        assert!(poly.is_zero());
+
+        poly.trim();
+
+        assert!(poly.is_zero());
+        assert!(poly.coefficient.is_empty());
+
+        let poly_1 = UnivariatePoly::new(vec![Fq::from(3), Fq::from(4)]);
+
+        assert!((poly_1.clone() * UnivariatePoly::zero()).is_zero());
+        assert_eq!(
+            (poly_1.clone() * UnivariatePoly::one()).coefficient,
+            poly_1.coefficient
+        );
+        assert_eq!(
+            (poly_1.clone() + UnivariatePoly::zero()).coefficient,
+            poly_1.coefficient
+        );
+    }
+
     #[test]
     fn it_evaluates_poly() {
         let poly_1 = UnivariatePoly {